@@ -0,0 +1,40 @@
+use std::thread;
+
+use kar_reversi_minmax::{
+    benchmark, bot::Bot, bot_algorithm::BotAlgorithm, bot_heuristic::BotHeuristic,
+};
+
+/// Compares `Parallel` with `NegaMax` on the benchmark positions: `parallel_benchmark [DEPTH]
+/// [THREADS]`, with one thread per core by default.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let depth = args.next().and_then(|d| d.parse().ok()).unwrap_or(7);
+    let threads = args
+        .next()
+        .and_then(|t| t.parse().ok())
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+
+    let mut negamax_time = None;
+    for algorithm in [BotAlgorithm::NegaMax, BotAlgorithm::Parallel] {
+        let bot =
+            Bot::new(algorithm, Some(depth), BotHeuristic::TacticalWeighting).with_threads(threads);
+        let result = benchmark::run(&bot);
+        let speedup = negamax_time.map_or(String::new(), |t: std::time::Duration| {
+            format!(
+                " | Speedup: {:.2}x",
+                t.as_secs_f64() / result.elapsed.as_secs_f64()
+            )
+        });
+        negamax_time.get_or_insert(result.elapsed);
+        println!(
+            "{}: {} positions in {:?} | Expansions: {} | Comparisons: {}{}",
+            algorithm,
+            result.positions,
+            result.elapsed,
+            result.metrics.expansions,
+            result.metrics.comparisons,
+            speedup
+        );
+    }
+    println!("{threads} threads");
+}
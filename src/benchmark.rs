@@ -0,0 +1,64 @@
+use std::time::Duration;
+use web_time::Instant;
+
+use crate::{
+    bot::Bot, bot_algorithm::BotAlgorithm, bot_difficulty::BotDifficulty,
+    bot_heuristic::BotHeuristic, bot_metrics::BotMetrics, history::Move, player::Player,
+    reversi::Reversi,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub struct BenchmarkResult {
+    pub positions: usize,
+    pub metrics: BotMetrics,
+    pub elapsed: Duration,
+}
+
+/// Early and midgame positions with Green to move, reached by stepping through each
+/// position's valid moves with a different stride per line so they are the same every run.
+pub fn positions() -> Vec<Reversi> {
+    let mut positions = Vec::new();
+    for stride in [1, 2, 3, 5] {
+        for plies in [10, 20, 30] {
+            let mut game = Reversi::new(Some((
+                Player::Green,
+                BotDifficulty::Easy,
+                BotAlgorithm::NegaMax,
+                BotHeuristic::UniformWeighting,
+            )));
+            game.update_valid_moves();
+            for ply in 0..plies {
+                if !game.valid_moves().is_empty() {
+                    let coord = game.valid_moves()[(ply * stride) % game.valid_moves().len()];
+                    game.make_move(Move::Place(coord));
+                } else {
+                    game.make_move(Move::Pass);
+                }
+            }
+            if game.current_player() == Player::Green && !game.valid_moves().is_empty() {
+                positions.push(game);
+            }
+        }
+    }
+    positions
+}
+
+/// Asks a fresh copy of `bot` for a move in every benchmark position.
+pub fn run(bot: &Bot) -> BenchmarkResult {
+    let positions = positions();
+    let mut metrics = BotMetrics::default();
+    let mut elapsed = Duration::ZERO;
+    for game in positions.iter() {
+        let mut bot = bot.clone();
+        let start = Instant::now();
+        bot.get_move(game.clone());
+        elapsed += start.elapsed();
+        metrics.merge(bot.get_metrics());
+    }
+
+    BenchmarkResult {
+        positions: positions.len(),
+        metrics,
+        elapsed,
+    }
+}
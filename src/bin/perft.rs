@@ -0,0 +1,25 @@
+//! Counts the lines of play from a position to each depth, to check move generation against
+//! known counts.
+//!
+//! ```text
+//! perft [--divide] [--position POSITION] DEPTH
+//! ```
+//!
+//! The same as the main binary's `perft` command. Positions are written as in
+//! `Reversi::from_position_str`, the start by default. With `--divide` the count at `DEPTH`
+//! is split by the first move.
+
+use std::{env, process};
+
+use kar_reversi_minmax::cli;
+
+fn main() {
+    let args = ["perft".to_string()]
+        .into_iter()
+        .chain(env::args().skip(1))
+        .collect();
+    if let Err(error) = cli::run(args) {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
@@ -0,0 +1,60 @@
+//! Plays bot configurations against each other without any display and prints the results.
+//!
+//! ```text
+//! tournament [--gauntlet] [--openings N] CONTESTANT CONTESTANT...
+//! ```
+//!
+//! Contestants are written `ALGORITHM:HEURISTIC[:DEPTH][:TIMEms]`, for example
+//! `AlphaBeta:TacticalWeighting:8 NegaMax:UniformWeighting:4`.
+
+use std::{env, process};
+
+use kar_reversi_minmax::tournament::{self, Contestant, TournamentFormat};
+
+const USAGE: &str = "usage: tournament [--gauntlet] [--openings N] CONTESTANT CONTESTANT...\n\
+contestants are written ALGORITHM:HEURISTIC[:DEPTH][:TIMEms]";
+
+fn main() {
+    if let Err(error) = run(env::args().skip(1).collect()) {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let mut format = TournamentFormat::RoundRobin;
+    let mut opening_count = 8;
+    let mut contestants: Vec<Contestant> = Vec::new();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--gauntlet" => format = TournamentFormat::Gauntlet,
+            "--openings" => {
+                let value = args
+                    .next()
+                    .ok_or(format!("--openings needs a value\n{USAGE}"))?;
+                opening_count = value
+                    .parse()
+                    .map_err(|_| format!("invalid number of openings {value}\n{USAGE}"))?;
+            }
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ => contestants.push(arg.parse().map_err(|e| format!("{e}\n{USAGE}"))?),
+        }
+    }
+    if contestants.len() < 2 {
+        return Err(format!("at least two contestants are needed\n{USAGE}"));
+    }
+
+    let openings = tournament::balanced_openings(opening_count);
+    println!(
+        "Playing a {format} between {} bots from {} openings, each with both colours...\n",
+        contestants.len(),
+        openings.len()
+    );
+    print!("{}", tournament::run(&contestants, format, &openings));
+    Ok(())
+}
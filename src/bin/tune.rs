@@ -0,0 +1,88 @@
+//! Fits the `Pattern` heuristic's weights to labelled positions and writes a weight file.
+//!
+//! ```text
+//! tune [POSITIONS] [--self-play GAMES] [--save-positions FILE] [--output FILE]
+//!      [--method LeastSquares|Logistic] [--epochs N] [--learning-rate RATE]
+//!      [--validation FRACTION] [--stages N] [--seed N]
+//! ```
+
+use std::{env, process, str::FromStr};
+
+use kar_reversi_minmax::tuner::{self, TuneSettings};
+
+const USAGE: &str = "usage: tune [POSITIONS] [--self-play GAMES] [--save-positions FILE] \
+[--output FILE] [--method LeastSquares|Logistic] [--epochs N] [--learning-rate RATE] \
+[--validation FRACTION] [--stages N] [--seed N]";
+
+fn main() {
+    if let Err(error) = run(env::args().skip(1).collect()) {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let mut positions_path = None;
+    let mut self_play_games = None;
+    let mut save_positions = None;
+    let mut output = String::from("pattern_weights.json");
+    let mut settings = TuneSettings::default();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{arg} needs a value\n{USAGE}"));
+        match arg.as_str() {
+            "--self-play" => self_play_games = Some(parse(&arg, &value()?)?),
+            "--save-positions" => save_positions = Some(value()?),
+            "--output" => output = value()?,
+            "--method" => settings.method = parse(&arg, &value()?)?,
+            "--epochs" => settings.epochs = parse(&arg, &value()?)?,
+            "--learning-rate" => settings.learning_rate = parse(&arg, &value()?)?,
+            "--validation" => settings.validation_fraction = parse(&arg, &value()?)?,
+            "--stages" => settings.stages = parse(&arg, &value()?)?,
+            "--seed" => settings.seed = parse(&arg, &value()?)?,
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            _ if !arg.starts_with("--") && positions_path.is_none() => positions_path = Some(arg),
+            _ => return Err(format!("unexpected argument {arg}\n{USAGE}")),
+        }
+    }
+
+    settings.validate().map_err(|e| e.to_string())?;
+
+    let mut positions = match &positions_path {
+        Some(path) => tuner::load_positions(path).map_err(|e| format!("{path}: {e}"))?,
+        None => Vec::new(),
+    };
+    if let Some(games) = self_play_games {
+        println!("Playing {games} self-play games...");
+        positions.extend(tuner::self_play_positions(games, settings.seed));
+    }
+    if positions_path.is_none() && self_play_games.is_none() {
+        return Err(format!("no positions to tune on\n{USAGE}"));
+    }
+    if let Some(path) = save_positions {
+        tuner::save_positions(&path, &positions).map_err(|e| format!("{path}: {e}"))?;
+    }
+
+    println!(
+        "Tuning on {} positions with {}...",
+        positions.len(),
+        settings.method
+    );
+    let (weights, report) = tuner::tune(&positions, &settings).map_err(|e| e.to_string())?;
+    weights
+        .save(&output)
+        .map_err(|e| format!("{output}: {e}"))?;
+    println!("{report}");
+    println!("Wrote {output}");
+    Ok(())
+}
+
+fn parse<T: FromStr>(arg: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value {value} for {arg}\n{USAGE}"))
+}
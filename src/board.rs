@@ -1,61 +1,343 @@
-use crate::player::Player;
+use crate::{constants::DIRECTIONS, error::Error, player::Player, zobrist};
 
 #[cfg(feature = "terminal")]
 use std::fmt::Display;
 
+/// One bit per square, indexed by `row * size + col`. Wide enough for a 10x10 board.
+pub(crate) type Bitboard = u128;
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Board {
-    board: Vec<Vec<Option<Player>>>,
+    green: Bitboard,
+    red: Bitboard,
     size: usize,
+    full: Bitboard,
+    not_first_col: Bitboard,
+    not_last_col: Bitboard,
+    zobrist_key: u64,
 }
 
 impl Board {
-    pub(crate) fn new(size: usize) -> Self {
-        if size < 6 || size % 2 == 1 {
-            panic!("size must be even")
+    /// The starting position on a board with `size` squares along each side.
+    ///
+    /// Panics if `size` is odd, below 6 or above 10; see [`Board::try_new`].
+    pub fn new(size: usize) -> Self {
+        Self::try_new(size).unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// The starting position on a board with `size` squares along each side, which must be
+    /// even and from 6 to 10.
+    pub fn try_new(size: usize) -> Result<Self, Error> {
+        if size < 6 || size % 2 == 1 || size * size > Bitboard::BITS as usize {
+            return Err(Error::InvalidBoardSize(size));
         }
 
-        let mut board = vec![vec![None; size]; size];
+        let full = Bitboard::MAX >> (Bitboard::BITS as usize - size * size);
+        let first_col = (0..size).fold(0, |mask, row| mask | 1 << (row * size));
+        let last_col = first_col << (size - 1);
+        let mut board = Self {
+            green: 0,
+            red: 0,
+            size,
+            full,
+            not_first_col: full & !first_col,
+            not_last_col: full & !last_col,
+            zobrist_key: 0,
+        };
+
         let mid = size / 2;
-        board[mid][mid] = Some(Player::Green);
-        board[mid - 1][mid - 1] = Some(Player::Green);
-        board[mid][mid - 1] = Some(Player::Red);
-        board[mid - 1][mid] = Some(Player::Red);
+        board.set((mid, mid), Some(Player::Green));
+        board.set((mid - 1, mid - 1), Some(Player::Green));
+        board.set((mid, mid - 1), Some(Player::Red));
+        board.set((mid - 1, mid), Some(Player::Red));
 
-        Self { board, size }
+        Ok(board)
+    }
+
+    /// Reads squares written by [`Board::to_squares_string`], onto a board with as many squares.
+    /// Empty squares can also be written as `.` or `_`, and whitespace is skipped.
+    pub fn from_squares_str(squares: &str) -> Result<Self, Error> {
+        let squares: Vec<char> = squares.chars().filter(|c| !c.is_whitespace()).collect();
+        let size = (squares.len() as f64).sqrt() as usize;
+        if squares.is_empty() {
+            return Err(Error::InvalidPosition(String::from(
+                "the position has no squares",
+            )));
+        }
+        if size * size != squares.len() {
+            return Err(Error::InvalidPosition(format!(
+                "{} squares do not make a square board",
+                squares.len()
+            )));
+        }
+
+        let mut board = Self::try_new(size)?;
+        for (i, &square) in squares.iter().enumerate() {
+            let player = match square {
+                '-' | '.' | '_' => None,
+                _ => Some(Player::from_symbol(square).ok_or_else(|| {
+                    Error::InvalidPosition(format!("unexpected square '{square}'"))
+                })?),
+            };
+            board.set((size - 1 - i / size, i % size), player);
+        }
+        Ok(board)
     }
 
-    pub(crate) fn size(&self) -> usize {
+    /// The squares as `X` (Green), `O` (Red) or `-`, from a1 along to h1 and on up the board
+    /// to h8 in the notation of [`transcript`](crate::transcript), as common Othello tools
+    /// write them.
+    pub fn to_squares_string(&self) -> String {
+        let size = self.size;
+        (0..size * size)
+            .map(|i| {
+                self.get((size - 1 - i / size, i % size))
+                    .map_or('-', |player| player.symbol())
+            })
+            .collect()
+    }
+
+    /// The number of squares along each side.
+    pub fn size(&self) -> usize {
         self.size
     }
 
-    pub(crate) fn get(&self, coord: (usize, usize)) -> Option<Player> {
-        debug_assert!(coord.0 < self.size && coord.1 < self.size);
+    /// Zobrist hash of the pieces on the board, kept up to date by every mutation.
+    pub(crate) fn zobrist_key(&self) -> u64 {
+        self.zobrist_key
+    }
+
+    /// Whether `coord` is a square on the board.
+    pub fn contains(&self, coord: (usize, usize)) -> bool {
+        coord.0 < self.size && coord.1 < self.size
+    }
+
+    /// The player with a piece on `coord`, if any. Panics if `coord` is off the board.
+    pub fn get(&self, coord: (usize, usize)) -> Option<Player> {
+        assert!(self.contains(coord), "{coord:?} is off the board");
+
+        let bit = self.bit(coord);
+        if self.green & bit != 0 {
+            Some(Player::Green)
+        } else if self.red & bit != 0 {
+            Some(Player::Red)
+        } else {
+            None
+        }
+    }
+
+    /// Puts `player`'s piece on `coord`, or empties it for `None`, to set up a position.
+    /// Panics if `coord` is off the board.
+    pub fn set(&mut self, coord: (usize, usize), player: Option<Player>) {
+        let previous = self.get(coord);
+        let bit = self.bit(coord);
+        if let Some(previous) = previous {
+            self.zobrist_key ^= zobrist::pieces_key(previous, bit);
+        }
+        self.green &= !bit;
+        self.red &= !bit;
+        if let Some(player) = player {
+            *self.bits_for_player_mut(player) |= bit;
+            self.zobrist_key ^= zobrist::pieces_key(player, bit);
+        }
+    }
 
-        self.board[coord.0][coord.1]
+    pub(crate) fn bits_for_player(&self, player: Player) -> Bitboard {
+        match player {
+            Player::Green => self.green,
+            Player::Red => self.red,
+        }
     }
 
-    pub(crate) fn set(&mut self, coord: (usize, usize), player: Option<Player>) {
-        self.board[coord.0][coord.1] = player;
+    fn bits_for_player_mut(&mut self, player: Player) -> &mut Bitboard {
+        match player {
+            Player::Green => &mut self.green,
+            Player::Red => &mut self.red,
+        }
+    }
+
+    pub(crate) fn empty_bits(&self) -> Bitboard {
+        self.full & !(self.green | self.red)
+    }
+
+    pub fn count_for_player(&self, player: Player) -> usize {
+        self.bits_for_player(player).count_ones() as usize
     }
 
     pub(crate) fn pieces_for_player(
         &self,
         player: Player,
     ) -> impl Iterator<Item = (usize, usize)> + '_ {
-        self.board.iter().enumerate().flat_map(move |(i, row)| {
-            row.iter().enumerate().filter_map(move |(j, val)| {
-                if val.is_some_and(|p| p == player) {
-                    Some((i, j))
-                } else {
-                    None
-                }
-            })
+        self.coords(self.bits_for_player(player))
+    }
+
+    /// Iterates the coordinates of every set bit in `bits`, in row-major order.
+    pub(crate) fn coords(&self, mut bits: Bitboard) -> impl Iterator<Item = (usize, usize)> + '_ {
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let index = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+            Some((index / self.size, index % self.size))
+        })
+    }
+
+    /// Every empty square where `player` would capture at least one piece.
+    pub(crate) fn valid_moves_bits(&self, player: Player) -> Bitboard {
+        let own = self.bits_for_player(player);
+        let opponent = self.bits_for_player(player.other());
+        let empty = self.empty_bits();
+
+        let mut moves = 0;
+        for direction in DIRECTIONS {
+            // a capturable run is at most `size - 2` pieces long
+            let mut run = self.shift(own, direction) & opponent;
+            for _ in 3..self.size {
+                run |= self.shift(run, direction) & opponent;
+            }
+            moves |= self.shift(run, direction) & empty;
+        }
+        moves
+    }
+
+    /// The pieces that would be flipped if `player` placed a piece at `coord`.
+    pub(crate) fn flips_for_move(&self, coord: (usize, usize), player: Player) -> Bitboard {
+        let own = self.bits_for_player(player);
+        let opponent = self.bits_for_player(player.other());
+        let start = self.bit(coord);
+
+        let mut flips = 0;
+        for direction in DIRECTIONS {
+            let mut run = 0;
+            let mut cursor = self.shift(start, direction);
+            while cursor & opponent != 0 {
+                run |= cursor;
+                cursor = self.shift(cursor, direction);
+            }
+            if cursor & own != 0 {
+                flips |= run;
+            }
+        }
+        flips
+    }
+
+    pub(crate) fn apply_move(&mut self, coord: (usize, usize), player: Player, flips: Bitboard) {
+        let bit = self.bit(coord);
+        *self.bits_for_player_mut(player) |= bit | flips;
+        *self.bits_for_player_mut(player.other()) &= !flips;
+        self.update_zobrist_key(bit, player, flips);
+    }
+
+    pub(crate) fn undo_move(&mut self, coord: (usize, usize), player: Player, flips: Bitboard) {
+        let bit = self.bit(coord);
+        *self.bits_for_player_mut(player) &= !(bit | flips);
+        *self.bits_for_player_mut(player.other()) |= flips;
+        self.update_zobrist_key(bit, player, flips);
+    }
+
+    /// Toggles `player`'s piece at `placed` and the owner of every flipped piece.
+    fn update_zobrist_key(&mut self, placed: Bitboard, player: Player, flips: Bitboard) {
+        self.zobrist_key ^= zobrist::pieces_key(player, placed | flips)
+            ^ zobrist::pieces_key(player.other(), flips);
+    }
+
+    pub(crate) fn index(&self, coord: (usize, usize)) -> usize {
+        coord.0 * self.size + coord.1
+    }
+
+    pub(crate) fn bit(&self, coord: (usize, usize)) -> Bitboard {
+        1 << self.index(coord)
+    }
+
+    pub(crate) fn corner_bits(&self) -> Bitboard {
+        let n = self.size - 1;
+        self.bit((0, 0)) | self.bit((0, n)) | self.bit((n, 0)) | self.bit((n, n))
+    }
+
+    /// The squares diagonally next to each corner.
+    pub(crate) fn x_square_bits(&self) -> Bitboard {
+        let n = self.size - 2;
+        self.bit((1, 1)) | self.bit((1, n)) | self.bit((n, 1)) | self.bit((n, n))
+    }
+
+    /// The edge squares next to each corner.
+    pub(crate) fn c_square_bits(&self) -> Bitboard {
+        let corners = self.corner_bits();
+        let neighbours = |direction| self.shift(corners, direction);
+        (neighbours((0, 1)) | neighbours((0, !0)) | neighbours((1, 0)) | neighbours((!0, 0)))
+            & !corners
+    }
+
+    /// Every square next to at least one bit in `bits`.
+    pub(crate) fn adjacent_bits(&self, bits: Bitboard) -> Bitboard {
+        DIRECTIONS.iter().fold(0, |adjacent, &direction| {
+            adjacent | self.shift(bits, direction)
         })
     }
 
-    pub(crate) fn switch_piece(&mut self, coord: (usize, usize)) {
-        self.board[coord.0][coord.1] = Some(self.board[coord.0][coord.1].unwrap().other());
+    /// `player`'s pieces that touch an empty square.
+    pub(crate) fn frontier_bits(&self, player: Player) -> Bitboard {
+        self.bits_for_player(player) & self.adjacent_bits(self.empty_bits())
+    }
+
+    /// `player`'s pieces that can never be flipped. A piece is stable when, along each of the
+    /// four lines through it, the line is full or one of its neighbours is the edge of the
+    /// board or another stable piece of the same player.
+    pub(crate) fn stable_bits(&self, player: Player) -> Bitboard {
+        let own = self.bits_for_player(player);
+        let occupied = self.green | self.red;
+
+        // squares whose neighbour in each direction is off the board
+        let edges = DIRECTIONS.map(|direction| {
+            self.full
+                & !self.shift(
+                    self.full,
+                    (direction.0.wrapping_neg(), direction.1.wrapping_neg()),
+                )
+        });
+        // squares from which every square in each direction up to the edge is occupied
+        let filled_rays: [Bitboard; 8] = std::array::from_fn(|i| {
+            let (i_dir, j_dir) = DIRECTIONS[i];
+            let mut ray = edges[i];
+            for _ in 1..self.size {
+                ray |= self.shift(ray & occupied, (i_dir.wrapping_neg(), j_dir.wrapping_neg()));
+            }
+            ray
+        });
+
+        let mut stable = 0;
+        loop {
+            let mut candidates = own;
+            for i in 0..4 {
+                let (forward, backward) = (DIRECTIONS[i], DIRECTIONS[i + 4]);
+                candidates &= filled_rays[i] & filled_rays[i + 4]
+                    | edges[i]
+                    | edges[i + 4]
+                    | self.shift(stable, forward)
+                    | self.shift(stable, backward);
+            }
+            if candidates == stable {
+                return stable;
+            }
+            stable = candidates;
+        }
+    }
+
+    /// Moves every bit one square in `direction`, dropping bits that leave the board.
+    fn shift(&self, bits: Bitboard, (i, j): (usize, usize)) -> Bitboard {
+        let offset = i as isize * self.size as isize + j as isize;
+        let shifted = if offset >= 0 {
+            bits << offset
+        } else {
+            bits >> -offset
+        };
+        shifted
+            & match j {
+                0 => self.full,
+                1 => self.not_first_col,
+                _ => self.not_last_col,
+            }
     }
 }
 
@@ -70,14 +352,17 @@ impl Display for Board {
             None => " ",
         };
 
+        // labelled as squares are named in transcripts and the terminal game
         let val_iter = (0..=self.size).map(|i| {
             (0..=self.size).map(move |j| {
-                if i == 0 {
-                    j.to_string()
+                if i == 0 && j == 0 {
+                    String::new()
+                } else if i == 0 {
+                    ((b'a' + j as u8 - 1) as char).to_string()
                 } else if j == 0 {
-                    i.to_string()
+                    (self.size - i + 1).to_string()
                 } else {
-                    player_to_string(self.board[i - 1][j - 1]).to_string()
+                    player_to_string(self.get((i - 1, j - 1))).to_string()
                 }
             })
         });
@@ -87,3 +372,51 @@ impl Display for Board {
         write!(f, "{}", table)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_new_rejects_unsupported_sizes() {
+        for size in [0, 4, 7, 12] {
+            assert!(matches!(
+                Board::try_new(size),
+                Err(Error::InvalidBoardSize(s)) if s == size
+            ));
+        }
+        assert_eq!(Board::try_new(10).unwrap().size(), 10);
+    }
+
+    #[test]
+    fn test_stable_bits() {
+        let mut board = Board::new(8);
+        assert_eq!(board.stable_bits(Player::Green), 0);
+
+        // a corner, the edge pieces anchored to it and one piece behind them
+        for coord in [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1)] {
+            board.set(coord, Some(Player::Green));
+        }
+        // an edge piece of the opponent's next to an empty square can still be flipped
+        board.set((0, 3), Some(Player::Red));
+        let stable = [(0, 0), (0, 1), (0, 2), (1, 0), (1, 1)]
+            .iter()
+            .fold(0, |bits, &coord| bits | board.bit(coord));
+        assert_eq!(board.stable_bits(Player::Green), stable);
+        assert_eq!(board.stable_bits(Player::Red), 0);
+
+        // every piece on a full board is stable
+        for row in 0..8 {
+            for col in 0..8 {
+                board.set(
+                    (row, col),
+                    Some([Player::Green, Player::Red][(row + col) % 2]),
+                );
+            }
+        }
+        assert_eq!(
+            board.stable_bits(Player::Green) | board.stable_bits(Player::Red),
+            board.full
+        );
+    }
+}
@@ -0,0 +1,25 @@
+/// The board sizes offered by the front ends. [`Board`](crate::board::Board) takes any even
+/// size from 6 to 10.
+#[derive(
+    Clone, Copy, PartialEq, Eq, Default, strum::Display, strum::EnumIter, strum::EnumString, Debug,
+)]
+pub enum BoardSize {
+    #[strum(serialize = "6x6")]
+    Six,
+    #[default]
+    #[strum(serialize = "8x8")]
+    Eight,
+    #[strum(serialize = "10x10")]
+    Ten,
+}
+
+impl BoardSize {
+    /// The number of squares along each side.
+    pub fn squares_per_side(self) -> usize {
+        match self {
+            Self::Six => 6,
+            Self::Eight => 8,
+            Self::Ten => 10,
+        }
+    }
+}
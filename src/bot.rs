@@ -1,19 +1,53 @@
-use async_recursion::async_recursion;
-use futures::{executor::block_on, future::join_all};
-use std::sync::{Arc, RwLock};
+use std::{
+    cmp::Reverse,
+    fmt,
+    sync::atomic::{AtomicI64, AtomicUsize, Ordering as AtomicOrdering},
+    thread,
+    time::Duration,
+};
+use web_time::Instant;
 
 use crate::{
-    board::Board, bot_algorithm::BotAlgorithm, bot_heuristic::BotHeuristic, player::Player,
+    board::{Bitboard, Board},
+    bot_algorithm::BotAlgorithm,
+    bot_difficulty::BotDifficulty,
+    bot_heuristic::BotHeuristic,
+    bot_metrics::BotMetrics,
+    endgame_solver::{self, EndgameMode},
+    error::Error,
+    mcts::{self, MctsSettings},
+    move_provider::{Analysis, ChosenMove, MoveProvider, PositionView, ProviderError},
+    pattern,
+    player::Player,
     reversi::Reversi,
+    transposition_table::{Bound, TranspositionTable},
 };
 
+type ScoreCompare = Box<dyn Fn(i64, i64) -> bool>;
+
+/// Nodes with fewer plies left than this fall back to history ordering, where a one-ply
+/// search would cost more than the cutoffs it finds.
+const SHALLOW_SEARCH_ORDERING_DEPTH: usize = 3;
+
 #[derive(Clone, Debug)]
 pub struct Bot {
     algorithm: BotAlgorithm,
     max_depth: Option<usize>,
     heuristic: BotHeuristic,
-    expansions: usize,
-    comparisons: usize,
+    time_limit: Option<Duration>,
+    endgame: Option<(usize, EndgameMode)>,
+    threads: usize,
+    shallow_search_ordering: bool,
+    mcts_settings: MctsSettings,
+    metrics: BotMetrics,
+    transposition_table: TranspositionTable,
+    killer_moves: Vec<[Option<(usize, usize)>; 2]>,
+    history_scores: Vec<usize>,
+    mtdf_guess: Option<i64>,
+    /// The player the bot is choosing a move for, whose score minmax and alphabeta maximise.
+    root_player: Player,
+    deadline: Option<Instant>,
+    is_out_of_time: bool,
 }
 
 impl Default for Bot {
@@ -22,8 +56,19 @@ impl Default for Bot {
             algorithm: BotAlgorithm::MinMax,
             max_depth: Default::default(),
             heuristic: BotHeuristic::UniformWeighting,
-            expansions: Default::default(),
-            comparisons: Default::default(),
+            time_limit: Default::default(),
+            endgame: Default::default(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            shallow_search_ordering: false,
+            mcts_settings: Default::default(),
+            metrics: Default::default(),
+            transposition_table: Default::default(),
+            killer_moves: Default::default(),
+            history_scores: vec![0; Bitboard::BITS as usize],
+            mtdf_guess: Default::default(),
+            root_player: Player::Green,
+            deadline: Default::default(),
+            is_out_of_time: Default::default(),
         }
     }
 }
@@ -38,36 +83,236 @@ impl Bot {
         }
     }
 
+    /// The bot behind each [`BotDifficulty`]: fixed depths (or MCTS iteration counts) that
+    /// grow with the difficulty, or iterative deepening for the timed ones, with the harder
+    /// ones solving the endgame exactly.
+    pub fn from_difficulty(
+        difficulty: BotDifficulty,
+        algorithm: BotAlgorithm,
+        heuristic: BotHeuristic,
+    ) -> Self {
+        type D = BotDifficulty;
+        let (max_depth, mcts_iterations, time_limit, endgame_empties) = match difficulty {
+            D::Easy => (Some(1), 200, None, None),
+            D::Medium => (Some(4), 2_000, None, None),
+            D::Hard => (Some(8), 20_000, None, Some(10)),
+            D::Insane => (Some(12), 200_000, None, Some(14)),
+            D::OneSecond => (None, 0, Some(Duration::from_secs(1)), Some(12)),
+            D::FiveSeconds => (None, 0, Some(Duration::from_secs(5)), Some(12)),
+            D::TenSeconds => (None, 0, Some(Duration::from_secs(10)), Some(12)),
+        };
+        Self::new(algorithm, max_depth, heuristic)
+            .with_time_limit(time_limit)
+            .with_endgame_solver(endgame_empties.map(|n| (n, EndgameMode::DiscDifferential)))
+            .with_mcts_settings(MctsSettings {
+                iterations: mcts_iterations,
+                ..Default::default()
+            })
+    }
+
+    /// Makes the bot search one ply deeper at a time until `time_limit` runs out, playing the
+    /// move from the deepest search that finished. Any `max_depth` still caps the search.
+    pub fn with_time_limit(mut self, time_limit: Option<Duration>) -> Self {
+        self.time_limit = time_limit;
+        self
+    }
+
+    /// Makes the bot solve the rest of the game exactly once at most `empties` squares are
+    /// left, instead of searching to its depth or time limit. A bot with a time limit gives
+    /// the solve half of it, and searches as usual if the solve does not finish.
+    pub fn with_endgame_solver(mut self, endgame: Option<(usize, EndgameMode)>) -> Self {
+        self.endgame = endgame;
+        self
+    }
+
+    /// Sets how many threads [`BotAlgorithm::Parallel`] searches with.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Orders moves near the root by a one-ply search instead of their history scores.
+    pub fn with_shallow_search_ordering(mut self, shallow_search_ordering: bool) -> Self {
+        self.shallow_search_ordering = shallow_search_ordering;
+        self
+    }
+
+    /// Sets the iteration count, playout policy and seed for [`BotAlgorithm::Mcts`].
+    pub fn with_mcts_settings(mut self, mcts_settings: MctsSettings) -> Self {
+        self.mcts_settings = mcts_settings;
+        self
+    }
+
+    /// Chooses the current player's move. Panics if they have none; see [`Bot::try_get_move`].
     pub fn get_move(&mut self, game: Reversi) -> (usize, usize) {
-        self.expansions = 0;
-        self.comparisons = 0;
+        self.try_get_move(game)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Chooses the current player's move, failing if they have none.
+    pub fn try_get_move(&mut self, game: Reversi) -> Result<(usize, usize), Error> {
+        if !Reversi::can_move(game.board(), game.current_player()) {
+            return Err(Error::NoLegalMoves(game.current_player()));
+        }
+        Ok(self.search(game))
+    }
+
+    fn search(&mut self, game: Reversi) -> (usize, usize) {
+        self.metrics = Default::default();
+        self.transposition_table.new_search();
+        self.killer_moves.clear();
+        self.history_scores.fill(0);
+        self.mtdf_guess = None;
+        self.root_player = game.current_player();
+        let started = Instant::now();
+        let empties = game.board().empty_bits().count_ones() as usize;
+        if let Some((_, mode)) = self.endgame.filter(|&(n, _)| empties <= n) {
+            // a timed bot gives the exact solve half its time and searches with what is left
+            // if that is not enough
+            let deadline = self.time_limit.map(|t| started + t / 2);
+            if let Some(coord) = self.get_move_endgame(&game, mode, deadline) {
+                self.metrics.depth_reached = empties;
+                return coord;
+            }
+        }
+        match self.time_limit {
+            Some(time_limit) if self.algorithm != BotAlgorithm::Mcts => self
+                .get_move_iterative_deepening(game, time_limit.saturating_sub(started.elapsed())),
+            _ => {
+                self.metrics.depth_reached = self
+                    .max_depth
+                    .unwrap_or(game.board().empty_bits().count_ones() as usize);
+                self.get_move_with_algorithm(game).unwrap()
+            }
+        }
+    }
+
+    fn get_move_with_algorithm(&mut self, game: Reversi) -> Option<(usize, usize)> {
         match self.algorithm {
             BotAlgorithm::MinMax => self.get_move_minmax(game),
             BotAlgorithm::AlphaBeta => self.get_move_alphabeta(game),
             BotAlgorithm::NegaMax => self.get_move_negamax(game),
-            BotAlgorithm::Async => self.get_move_async(game),
+            BotAlgorithm::Parallel => self.get_move_parallel(game),
+            BotAlgorithm::Pvs => self.get_move_pvs(game),
+            BotAlgorithm::MtdF => self.get_move_mtdf(game),
+            BotAlgorithm::Mcts => self.get_move_mcts(game),
+        }
+    }
+
+    /// The move that solves the game, or `None` if the solve does not finish by `deadline`.
+    fn get_move_endgame(
+        &mut self,
+        game: &Reversi,
+        mode: EndgameMode,
+        deadline: Option<Instant>,
+    ) -> Option<(usize, usize)> {
+        let (coord, _) = endgame_solver::best_move(
+            game.board(),
+            game.current_player(),
+            mode,
+            deadline,
+            &mut self.metrics.endgame_nodes,
+        )?;
+        Some(coord)
+    }
+
+    /// Runs for the bot's time limit if it has one, otherwise for its MCTS iteration count.
+    fn get_move_mcts(&mut self, game: Reversi) -> Option<(usize, usize)> {
+        mcts::best_move(
+            game.board(),
+            game.current_player(),
+            self.mcts_settings,
+            self.heuristic,
+            self.time_limit.map(|t| Instant::now() + t),
+            &mut self.metrics,
+        )
+    }
+
+    fn get_move_iterative_deepening(
+        &mut self,
+        game: Reversi,
+        time_limit: Duration,
+    ) -> (usize, usize) {
+        let deadline = Instant::now() + time_limit;
+        let max_depth = self.max_depth;
+        let last_depth = max_depth
+            .unwrap_or(usize::MAX)
+            .min(game.board().empty_bits().count_ones() as usize)
+            .max(1);
+
+        let mut best_move = None;
+        for depth in 1..=last_depth {
+            self.max_depth = Some(depth);
+            // the first iteration always finishes so there is a move to play
+            self.deadline = (depth > 1).then_some(deadline);
+            let coord = self.get_move_with_algorithm(game.clone());
+            if self.is_out_of_time {
+                break;
+            }
+            best_move = coord;
+            self.metrics.depth_reached = depth;
+            if Instant::now() >= deadline {
+                break;
+            }
+        }
+
+        self.max_depth = max_depth;
+        self.deadline = None;
+        self.is_out_of_time = false;
+        best_move.unwrap()
+    }
+
+    /// Whether the current search has run past its deadline and should be abandoned.
+    fn out_of_time(&mut self) -> bool {
+        if !self.is_out_of_time && self.metrics.expansions.is_multiple_of(256) {
+            self.is_out_of_time = self.deadline.is_some_and(|d| Instant::now() >= d);
         }
+        self.is_out_of_time
     }
 
     #[cfg(feature = "terminal")]
     pub fn show_metrics(&self) {
-        println!(
-            "This turn's metrics:\n\
-            Expansions: {} | Comparisons: {}",
-            self.expansions, self.comparisons
-        );
+        println!("This turn's metrics:\n{}", self.metrics);
     }
 
-    fn eval(heuristic: BotHeuristic, board: &Board, player: Player) -> i64 {
+    /// Scores `board` from `player`'s point of view, with `to_move` the player whose turn it
+    /// is.
+    pub(crate) fn eval(
+        heuristic: BotHeuristic,
+        board: &Board,
+        player: Player,
+        to_move: Player,
+    ) -> i64 {
         match heuristic {
             BotHeuristic::UniformWeighting => Self::uniform_eval(board, player),
             BotHeuristic::TacticalWeighting => Self::tactical_eval(board, player),
+            BotHeuristic::Mobility => Self::mobility_eval(board, player),
+            BotHeuristic::PotentialMobility => Self::potential_mobility_eval(board, player),
+            BotHeuristic::Frontier => Self::frontier_eval(board, player),
+            BotHeuristic::Stability => Self::stability_eval(board, player),
+            BotHeuristic::Parity => Self::parity_eval(board, player, to_move),
+            BotHeuristic::Pattern => pattern::evaluate(board, player),
+            BotHeuristic::Composite(weights) => {
+                let squares = board.size() * board.size();
+                let weights = weights.for_phase(board.empty_bits().count_ones() as usize, squares);
+                weights.discs * Self::uniform_eval(board, player)
+                    + weights.positional * Self::tactical_eval(board, player)
+                    + weights.mobility * Self::mobility_eval(board, player)
+                    + weights.potential_mobility * Self::potential_mobility_eval(board, player)
+                    + weights.frontier * Self::frontier_eval(board, player)
+                    + weights.stability * Self::stability_eval(board, player)
+                    + weights.parity * Self::parity_eval(board, player, to_move)
+            }
         }
     }
 
+    /// `count(player) - count(opponent)`.
+    fn difference(player: Player, count: impl Fn(Player) -> Bitboard) -> i64 {
+        count(player).count_ones() as i64 - count(player.other()).count_ones() as i64
+    }
+
     fn uniform_eval(board: &Board, player: Player) -> i64 {
-        board.pieces_for_player(player).count() as i64
-            - board.pieces_for_player(player.other()).count() as i64
+        board.count_for_player(player) as i64 - board.count_for_player(player.other()) as i64
     }
 
     fn tactical_eval(board: &Board, player: Player) -> i64 {
@@ -81,6 +326,40 @@ impl Bot {
                 .sum::<i64>()
     }
 
+    fn mobility_eval(board: &Board, player: Player) -> i64 {
+        Self::difference(player, |p| board.valid_moves_bits(p))
+    }
+
+    fn potential_mobility_eval(board: &Board, player: Player) -> i64 {
+        Self::difference(player, |p| {
+            board.empty_bits() & board.adjacent_bits(board.bits_for_player(p.other()))
+        })
+    }
+
+    /// Frontier pieces are a liability, so fewer is better.
+    fn frontier_eval(board: &Board, player: Player) -> i64 {
+        -Self::difference(player, |p| board.frontier_bits(p))
+    }
+
+    fn stability_eval(board: &Board, player: Player) -> i64 {
+        Self::difference(player, |p| board.stable_bits(p))
+    }
+
+    /// Without further passes, the player to move makes the last move exactly when an odd
+    /// number of squares is empty.
+    fn parity_eval(board: &Board, player: Player, to_move: Player) -> i64 {
+        let last_mover = if board.empty_bits().count_ones() % 2 == 1 {
+            to_move
+        } else {
+            to_move.other()
+        };
+        if last_mover == player {
+            1
+        } else {
+            -1
+        }
+    }
+
     fn get_tactical_eval_score_for_coord(board: &Board, coord: (usize, usize)) -> i64 {
         match (
             coord.0 == 0 || coord.0 == board.size() - 1,
@@ -93,33 +372,312 @@ impl Bot {
         }
     }
 
-    fn get_move_minmax(&mut self, mut game: Reversi) -> (usize, usize) {
-        self.minmax(&mut game, 0).1.unwrap()
+    fn get_move_minmax(&mut self, mut game: Reversi) -> Option<(usize, usize)> {
+        self.minmax(&mut game, 0).1
     }
 
-    fn get_move_alphabeta(&mut self, mut game: Reversi) -> (usize, usize) {
-        self.alphabeta(&mut game, 0, i64::MIN, i64::MAX).1.unwrap()
+    fn get_move_alphabeta(&mut self, mut game: Reversi) -> Option<(usize, usize)> {
+        self.alphabeta(&mut game, 0, i64::MIN, i64::MAX).1
     }
 
-    fn get_move_negamax(&mut self, mut game: Reversi) -> (usize, usize) {
-        self.negamax(&mut game, 0, i64::MIN, i64::MAX).1.unwrap()
+    fn get_move_negamax(&mut self, mut game: Reversi) -> Option<(usize, usize)> {
+        self.negamax(&mut game, 0, -i64::MAX, i64::MAX).1
     }
 
-    fn get_move_async(&mut self, game: Reversi) -> (usize, usize) {
-        let self_arc = Arc::new(RwLock::new(self.clone()));
-        let res = block_on(Self::async_negamax(
-            self_arc.clone(),
-            game.board().clone(),
-            self.heuristic,
-            game.current_player(),
-            game.bot_player().unwrap().0,
-            self.max_depth,
-            0,
-            i64::MIN,
-            i64::MAX,
-        ));
-        *self = self_arc.read().unwrap().clone();
-        res.1.unwrap()
+    fn get_move_pvs(&mut self, mut game: Reversi) -> Option<(usize, usize)> {
+        self.pvs(&mut game, 0, -i64::MAX, i64::MAX).1
+    }
+
+    /// Closes in on the root's score with null-window negamax searches, which are cheap to
+    /// repeat because each one reuses the transposition table filled by the last. The first
+    /// guess is the score from the previous iterative deepening iteration, if there was one.
+    fn get_move_mtdf(&mut self, mut game: Reversi) -> Option<(usize, usize)> {
+        let (mut lower, mut upper) = (-i64::MAX, i64::MAX);
+        let mut guess = self.mtdf_guess.unwrap_or_default();
+        let mut best_move = None;
+        while lower < upper && !self.is_out_of_time {
+            let beta = if guess == lower { guess + 1 } else { guess };
+            let (score, coord) = self.negamax(&mut game, 0, beta - 1, beta);
+            // a pass that fails low only bounds the score from above, so its move is not
+            // known to be best
+            if score < beta {
+                upper = score;
+            } else {
+                lower = score;
+                best_move = coord;
+            }
+            guess = score;
+        }
+        self.mtdf_guess = Some(guess);
+        best_move
+    }
+
+    /// Young Brothers Wait at the root: the first move is searched alone to give a bound,
+    /// then the remaining moves are shared out between threads that all prune against the
+    /// best score found so far.
+    fn get_move_parallel(&mut self, mut game: Reversi) -> Option<(usize, usize)> {
+        self.metrics.expansions += 1;
+        let (_, tt_move) =
+            self.probe_transposition_table(game.zobrist_key(), 0, -i64::MAX, i64::MAX);
+        let moves = self.ordered_moves(&game, 0, tt_move);
+        let (&eldest, younger) = moves.split_first()?;
+
+        let eldest_score = self.negamax_after_move(&mut game, eldest, -i64::MAX);
+        let alpha = AtomicI64::new(eldest_score);
+        let next_move = AtomicUsize::new(0);
+        let threads = self.threads.min(younger.len());
+        let improvements = if threads > 1 {
+            let workers: Vec<_> = thread::scope(|scope| {
+                let handles: Vec<_> = (0..threads)
+                    .map(|_| {
+                        let (mut bot, mut game) = (self.worker(), game.clone());
+                        let (alpha, next_move) = (&alpha, &next_move);
+                        scope.spawn(move || {
+                            let improvements =
+                                bot.search_younger_brothers(&mut game, younger, alpha, next_move);
+                            (bot, improvements)
+                        })
+                    })
+                    .collect();
+                handles.into_iter().map(|h| h.join().unwrap()).collect()
+            });
+
+            let mut improvements = Vec::new();
+            for (bot, mut worker_improvements) in workers {
+                self.metrics.merge(bot.metrics);
+                self.is_out_of_time |= bot.is_out_of_time;
+                improvements.append(&mut worker_improvements);
+            }
+            improvements
+        } else {
+            self.search_younger_brothers(&mut game, younger, &alpha, &next_move)
+        };
+
+        // ties go to the earlier move so the choice doesn't depend on thread timing
+        let best = improvements
+            .into_iter()
+            .max_by_key(|&(score, i)| (score, std::cmp::Reverse(i)));
+        match best {
+            Some((score, i)) if score > eldest_score => Some(younger[i]),
+            _ => Some(eldest),
+        }
+    }
+
+    /// A copy of the bot for a [`BotAlgorithm::Parallel`] worker thread, with metrics of its
+    /// own but the same transposition table, so every worker prunes with what the others found.
+    fn worker(&self) -> Self {
+        Self {
+            algorithm: self.algorithm,
+            max_depth: self.max_depth,
+            heuristic: self.heuristic,
+            time_limit: self.time_limit,
+            endgame: self.endgame,
+            threads: self.threads,
+            shallow_search_ordering: self.shallow_search_ordering,
+            mcts_settings: self.mcts_settings,
+            metrics: Default::default(),
+            transposition_table: self.transposition_table.share(),
+            killer_moves: self.killer_moves.clone(),
+            history_scores: self.history_scores.clone(),
+            mtdf_guess: self.mtdf_guess,
+            root_player: self.root_player,
+            deadline: self.deadline,
+            is_out_of_time: self.is_out_of_time,
+        }
+    }
+
+    /// Takes moves from `moves` until none are left, returning the score and index of every
+    /// move that beat the shared bound at the time it was searched.
+    fn search_younger_brothers(
+        &mut self,
+        game: &mut Reversi,
+        moves: &[(usize, usize)],
+        alpha: &AtomicI64,
+        next_move: &AtomicUsize,
+    ) -> Vec<(i64, usize)> {
+        let mut improvements = Vec::new();
+        loop {
+            let i = next_move.fetch_add(1, AtomicOrdering::Relaxed);
+            let Some(&m) = moves.get(i) else {
+                break;
+            };
+            let bound = alpha.load(AtomicOrdering::Relaxed);
+            let score = self.negamax_after_move(game, m, bound);
+            if self.is_out_of_time {
+                break;
+            }
+            if score > bound {
+                alpha.fetch_max(score, AtomicOrdering::Relaxed);
+                improvements.push((score, i));
+            }
+        }
+        improvements
+    }
+
+    /// Plays `m` and scores it for the player who made it, in the window `(alpha, MAX)`.
+    fn negamax_after_move(&mut self, game: &mut Reversi, m: (usize, usize), alpha: i64) -> i64 {
+        game.place_piece_and_add_history(m);
+        game.switch_players();
+        game.update_valid_moves();
+        let (score, _) = self.negamax(game, 1, -i64::MAX, -alpha);
+        game.undo_turn();
+        game.update_valid_moves();
+
+        self.metrics.comparisons += 1;
+        -score
+    }
+
+    /// Plies left to search below `depth`, or unbounded if the bot has no depth limit.
+    fn remaining_depth(&self, depth: usize) -> usize {
+        self.max_depth.map_or(usize::MAX, |md| md - depth)
+    }
+
+    /// Looks up the current position, returning a score that decides this node (never at
+    /// the root, which must produce a move) and the best move found by an earlier search.
+    fn probe_transposition_table(
+        &mut self,
+        key: u64,
+        depth: usize,
+        alpha: i64,
+        beta: i64,
+    ) -> (Option<i64>, Option<(usize, usize)>) {
+        let remaining_depth = self.remaining_depth(depth);
+        match self.transposition_table.get(key) {
+            Some(entry) => {
+                self.metrics.transposition_hits += 1;
+                let score = if depth > 0 {
+                    entry.cutoff(remaining_depth, alpha, beta)
+                } else {
+                    None
+                };
+                (score, entry.best_move)
+            }
+            None => (None, None),
+        }
+    }
+
+    fn store_transposition(
+        &mut self,
+        key: u64,
+        depth: usize,
+        score: i64,
+        (alpha, beta): (i64, i64),
+        best_move: Option<(usize, usize)>,
+    ) {
+        if self.is_out_of_time {
+            return;
+        }
+
+        let bound = if score <= alpha {
+            Bound::Upper
+        } else if score >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        let remaining_depth = self.remaining_depth(depth);
+        self.transposition_table
+            .store(key, remaining_depth, score, bound, best_move);
+    }
+
+    /// [`Bot::probe_transposition_table`] for minmax and alphabeta, whose scores are from the
+    /// root player's point of view where the table's are from the player to move's.
+    fn probe_for_root_player(
+        &mut self,
+        game: &Reversi,
+        key: u64,
+        depth: usize,
+        (alpha, beta): (i64, i64),
+    ) -> (Option<i64>, Option<(usize, usize)>) {
+        if game.current_player() == self.root_player {
+            return self.probe_transposition_table(key, depth, alpha, beta);
+        }
+        let (score, best_move) = self.probe_transposition_table(
+            key,
+            depth,
+            beta.saturating_neg(),
+            alpha.saturating_neg(),
+        );
+        (score.map(i64::saturating_neg), best_move)
+    }
+
+    /// [`Bot::store_transposition`] for minmax and alphabeta; see
+    /// [`Bot::probe_for_root_player`].
+    fn store_for_root_player(
+        &mut self,
+        game: &Reversi,
+        key: u64,
+        depth: usize,
+        score: i64,
+        (alpha, beta): (i64, i64),
+        best_move: Option<(usize, usize)>,
+    ) {
+        if game.current_player() == self.root_player {
+            self.store_transposition(key, depth, score, (alpha, beta), best_move);
+        } else {
+            let window = (beta.saturating_neg(), alpha.saturating_neg());
+            self.store_transposition(key, depth, score.saturating_neg(), window, best_move);
+        }
+    }
+
+    /// The current player's valid moves, searched in order of how likely they are to cause a
+    /// cutoff: the transposition table's move, corners, killer moves, then the rest by history
+    /// score (or a one-ply search, if enabled), with the squares next to corners last.
+    /// Ties keep board order so repeated searches expand the same nodes.
+    fn ordered_moves(
+        &self,
+        game: &Reversi,
+        depth: usize,
+        tt_move: Option<(usize, usize)>,
+    ) -> Vec<(usize, usize)> {
+        let board = game.board();
+        let player = game.current_player();
+        let killers = self.killer_moves.get(depth).copied().unwrap_or_default();
+        let use_shallow_search = self.shallow_search_ordering
+            && self.remaining_depth(depth) >= SHALLOW_SEARCH_ORDERING_DEPTH;
+
+        let mut moves = game.valid_moves().to_vec();
+        moves.sort_by_cached_key(|&m| {
+            let bit = board.bit(m);
+            let priority = if tt_move == Some(m) {
+                3
+            } else if board.corner_bits() & bit != 0 {
+                2
+            } else if killers.contains(&Some(m)) {
+                1
+            } else if board.x_square_bits() & bit != 0 {
+                -2
+            } else if board.c_square_bits() & bit != 0 {
+                -1
+            } else {
+                0
+            };
+            let score = if use_shallow_search {
+                let mut child = board.clone();
+                Reversi::place_piece_on_board(&mut child, m, player);
+                Self::eval(self.heuristic, &child, player, player.other())
+            } else {
+                self.history_scores[board.index(m)] as i64
+            };
+            Reverse((priority, score))
+        });
+        moves
+    }
+
+    /// Remembers a move that caused a cutoff so that it is tried early in sibling positions
+    /// (as a killer move) and everywhere else (through its history score).
+    fn record_cutoff(&mut self, board: &Board, depth: usize, m: (usize, usize)) {
+        if self.killer_moves.len() <= depth {
+            self.killer_moves.resize(depth + 1, Default::default());
+        }
+        let killers = &mut self.killer_moves[depth];
+        if killers[0] != Some(m) {
+            killers[1] = killers[0];
+            killers[0] = Some(m);
+        }
+
+        let remaining_depth = self.remaining_depth(depth).min(32);
+        self.history_scores[board.index(m)] += remaining_depth * remaining_depth;
     }
 
     fn minmax(&mut self, game: &mut Reversi, depth: usize) -> (i64, Option<(usize, usize)>) {
@@ -128,23 +686,34 @@ impl Bot {
                 Self::eval(
                     self.heuristic,
                     game.board(),
-                    game.bot_player().as_ref().unwrap().0,
+                    self.root_player,
+                    game.current_player(),
                 ),
                 None,
             );
         }
 
-        self.expansions += 1;
+        self.metrics.expansions += 1;
+        if self.out_of_time() {
+            return (0, None);
+        }
+        let key = game.zobrist_key();
+        let (tt_score, tt_move) =
+            self.probe_for_root_player(game, key, depth, (i64::MIN, i64::MAX));
+        if let Some(score) = tt_score {
+            return (score, tt_move);
+        }
+
         if !Reversi::can_move(game.board(), game.current_player()) {
             game.switch_players();
             game.update_valid_moves();
             let res = self.minmax(game, depth + 1);
             game.switch_players();
             game.update_valid_moves();
-            return res;
+            res
         } else {
-            let (mut score, score_compare): (_, Box<dyn Fn(i64, i64) -> bool>) =
-                if game.current_player() == game.bot_player().unwrap().0 {
+            let (mut score, score_compare): (_, ScoreCompare) =
+                if game.current_player() == self.root_player {
                     (
                         i64::MIN,
                         Box::new(|new_score: i64, score: i64| new_score > score),
@@ -156,7 +725,7 @@ impl Bot {
                     )
                 };
             let mut coord = None;
-            for m in game.valid_moves().to_vec() {
+            for m in self.ordered_moves(game, depth, tt_move) {
                 game.place_piece_and_add_history(m);
                 game.switch_players();
                 game.update_valid_moves();
@@ -164,12 +733,13 @@ impl Bot {
                 game.undo_turn();
                 game.update_valid_moves();
 
-                self.comparisons += 1;
+                self.metrics.comparisons += 1;
                 if score_compare(new_score, score) {
                     score = new_score;
                     coord = Some(m);
                 }
             }
+            self.store_for_root_player(game, key, depth, score, (i64::MIN, i64::MAX), coord);
             (score, coord)
         }
     }
@@ -180,205 +750,472 @@ impl Bot {
         depth: usize,
         mut alpha: i64,
         mut beta: i64,
-    ) -> (i64, Option<(usize, usize)>, (i64, i64)) {
+    ) -> (i64, Option<(usize, usize)>) {
         if !Reversi::anyone_can_move(game.board()) || self.max_depth.is_some_and(|md| depth >= md) {
             return (
                 Self::eval(
                     self.heuristic,
                     game.board(),
-                    game.bot_player().as_ref().unwrap().0,
+                    self.root_player,
+                    game.current_player(),
                 ),
                 None,
-                (alpha, beta),
             );
         }
 
-        self.expansions += 1;
+        self.metrics.expansions += 1;
+        if self.out_of_time() {
+            return (0, None);
+        }
+        let key = game.zobrist_key();
+        let (tt_score, tt_move) = self.probe_for_root_player(game, key, depth, (alpha, beta));
+        if let Some(score) = tt_score {
+            return (score, tt_move);
+        }
+
         if !Reversi::can_move(game.board(), game.current_player()) {
             game.switch_players();
             game.update_valid_moves();
             let res = self.alphabeta(game, depth + 1, alpha, beta);
             game.switch_players();
             game.update_valid_moves();
-            return res;
+            res
         } else {
-            let (mut score, is_newscore_better, do_alphabeta_prune): (
-                _,
-                Box<dyn Fn(i64, i64) -> bool>,
-                Box<dyn Fn(i64, i64) -> bool>,
-            ) = if game.current_player() == game.bot_player().unwrap().0 {
-                (
-                    i64::MIN,
-                    Box::new(|new_score: i64, score: i64| new_score > score),
-                    Box::new(|alpha: i64, beta: i64| alpha > beta),
-                )
-            } else {
-                (
-                    i64::MAX,
-                    Box::new(|new_score: i64, score: i64| new_score < score),
-                    Box::new(|alpha: i64, beta: i64| alpha < beta),
-                )
-            };
+            let window = (alpha, beta);
+            let is_maximising = game.current_player() == self.root_player;
+            let mut score = if is_maximising { i64::MIN } else { i64::MAX };
             let mut coord = None;
-            for m in game.valid_moves().to_vec() {
+            for m in self.ordered_moves(game, depth, tt_move) {
                 game.place_piece_and_add_history(m);
                 game.switch_players();
                 game.update_valid_moves();
-                let (new_score, _, (new_alpha, new_beta)) =
-                    self.alphabeta(game, depth + 1, alpha, beta);
-                (alpha, beta) = (alpha.max(new_alpha), beta.min(new_beta));
+                let (new_score, _) = self.alphabeta(game, depth + 1, alpha, beta);
                 game.undo_turn();
                 game.update_valid_moves();
 
-                self.comparisons += 1;
-                if is_newscore_better(new_score, score) {
-                    score = new_score;
-                    coord = Some(m);
+                self.metrics.comparisons += 1;
+                if is_maximising {
+                    if new_score > score {
+                        score = new_score;
+                        coord = Some(m);
+                    }
+                    alpha = alpha.max(score);
+                } else {
+                    if new_score < score {
+                        score = new_score;
+                        coord = Some(m);
+                    }
+                    beta = beta.min(score);
                 }
-                if do_alphabeta_prune(alpha, beta) {
+                if alpha >= beta {
+                    self.record_cutoff(game.board(), depth, m);
                     break;
                 }
             }
-            (score, coord, (alpha, beta))
+            self.store_for_root_player(game, key, depth, score, window, coord);
+            (score, coord)
         }
     }
 
-    fn negamax(
+    pub(crate) fn negamax(
         &mut self,
         game: &mut Reversi,
         depth: usize,
         mut alpha: i64,
-        mut beta: i64,
-    ) -> (i64, Option<(usize, usize)>, (i64, i64)) {
+        beta: i64,
+    ) -> (i64, Option<(usize, usize)>) {
         if !Reversi::anyone_can_move(game.board()) || self.max_depth.is_some_and(|md| depth >= md) {
             return (
-                Self::eval(self.heuristic, game.board(), game.current_player()),
+                Self::eval(
+                    self.heuristic,
+                    game.board(),
+                    game.current_player(),
+                    game.current_player(),
+                ),
                 None,
-                (alpha, beta),
             );
         }
 
-        self.expansions += 1;
+        self.metrics.expansions += 1;
+        if self.out_of_time() {
+            return (0, None);
+        }
+        let key = game.zobrist_key();
+        let (tt_score, tt_move) = self.probe_transposition_table(key, depth, alpha, beta);
+        if let Some(score) = tt_score {
+            return (score, tt_move);
+        }
+
         if !Reversi::can_move(game.board(), game.current_player()) {
             game.switch_players();
             game.update_valid_moves();
-            let res = self.negamax(game, depth + 1, alpha, beta);
+            let (score, _) = self.negamax(game, depth + 1, -beta, -alpha);
             game.switch_players();
             game.update_valid_moves();
-            return res;
+            (-score, None)
         } else {
+            let window = (alpha, beta);
             let mut score = i64::MIN;
             let mut coord = None;
-            for m in game.valid_moves().to_vec() {
+            for m in self.ordered_moves(game, depth, tt_move) {
                 game.place_piece_and_add_history(m);
                 game.switch_players();
                 game.update_valid_moves();
-                let (mut new_score, _, (new_alpha, new_beta)) =
-                    self.negamax(game, depth + 1, alpha, beta);
-                new_score *= -1;
-                (alpha, beta) = (alpha.max(new_alpha), beta.min(new_beta));
+                let (new_score, _) = self.negamax(game, depth + 1, -beta, -alpha);
+                let new_score = -new_score;
                 game.undo_turn();
                 game.update_valid_moves();
 
-                self.comparisons += 1;
+                self.metrics.comparisons += 1;
                 if new_score > score {
                     score = new_score;
                     coord = Some(m);
                 }
-                if alpha > beta {
+                alpha = alpha.max(score);
+                if alpha >= beta {
+                    self.record_cutoff(game.board(), depth, m);
                     break;
                 }
             }
-            (score, coord, (beta, alpha))
+            self.store_transposition(key, depth, score, window, coord);
+            (score, coord)
         }
     }
 
-    #[async_recursion]
-    async fn async_negamax(
-        bot: Arc<RwLock<Bot>>,
-        board: Board,
-        heuristic: BotHeuristic,
-        current_player: Player,
-        bot_player: Player,
-        max_depth: Option<usize>,
+    /// Negamax that searches the first (best-ordered) move with the full window and every other
+    /// move with a null window, only re-searching when one proves better than the first.
+    pub(crate) fn pvs(
+        &mut self,
+        game: &mut Reversi,
         depth: usize,
         mut alpha: i64,
-        mut beta: i64,
-    ) -> (i64, Option<(usize, usize)>, (i64, i64)) {
-        if !Reversi::anyone_can_move(&board) || max_depth.is_some_and(|md| depth >= md) {
+        beta: i64,
+    ) -> (i64, Option<(usize, usize)>) {
+        if !Reversi::anyone_can_move(game.board()) || self.max_depth.is_some_and(|md| depth >= md) {
             return (
-                Self::eval(heuristic, &board, current_player),
+                Self::eval(
+                    self.heuristic,
+                    game.board(),
+                    game.current_player(),
+                    game.current_player(),
+                ),
                 None,
-                (alpha, beta),
             );
         }
 
-        {
-            let mut bot = bot.write().unwrap();
-            bot.expansions += 1;
-            if bot.expansions % 10000 == 0 {
-                println!("{}", bot.expansions);
-            }
+        self.metrics.expansions += 1;
+        if self.out_of_time() {
+            return (0, None);
         }
-        if !Reversi::can_move(&board, current_player) {
-            Self::async_negamax(
-                bot,
-                board,
-                heuristic,
-                current_player.other(),
-                bot_player,
-                max_depth,
-                depth + 1,
-                beta,
-                alpha,
-            )
-            .await
+        let key = game.zobrist_key();
+        let (tt_score, tt_move) = self.probe_transposition_table(key, depth, alpha, beta);
+        if let Some(score) = tt_score {
+            return (score, tt_move);
+        }
+
+        if !Reversi::can_move(game.board(), game.current_player()) {
+            game.switch_players();
+            game.update_valid_moves();
+            let (score, _) = self.pvs(game, depth + 1, -beta, -alpha);
+            game.switch_players();
+            game.update_valid_moves();
+            (-score, None)
         } else {
+            let window = (alpha, beta);
             let mut score = i64::MIN;
             let mut coord = None;
-            let valid_moves: Vec<_> =
-                Reversi::get_valid_moves_for_player(&board, current_player).collect();
-            let mut futures = Vec::new();
-            for &m in &valid_moves {
-                let mut new_board = board.clone();
-                Reversi::place_piece_on_board(&mut new_board, m, current_player);
-                futures.push(Self::async_negamax(
-                    bot.clone(),
-                    new_board,
-                    heuristic,
-                    current_player.other(),
-                    bot_player,
-                    max_depth,
-                    depth + 1,
-                    beta,
-                    alpha,
-                ));
-            }
-
-            let results = valid_moves
+            for (i, m) in self
+                .ordered_moves(game, depth, tt_move)
                 .into_iter()
-                .zip(join_all(futures.into_iter()).await.into_iter());
-            for (m, (mut new_score, _, (new_alpha, new_beta))) in results {
-                {
-                    let mut bot = bot.write().unwrap();
-                    bot.comparisons += 1;
-                }
-                new_score *= -1;
-                (alpha, beta) = (alpha.max(new_alpha), beta.min(new_beta));
+                .enumerate()
+            {
+                game.place_piece_and_add_history(m);
+                game.switch_players();
+                game.update_valid_moves();
+                let new_score = if i == 0 {
+                    -self.pvs(game, depth + 1, -beta, -alpha).0
+                } else {
+                    let scout_score = -self.pvs(game, depth + 1, -alpha - 1, -alpha).0;
+                    if scout_score > alpha && scout_score < beta {
+                        -self.pvs(game, depth + 1, -beta, -alpha).0
+                    } else {
+                        scout_score
+                    }
+                };
+                game.undo_turn();
+                game.update_valid_moves();
+
+                self.metrics.comparisons += 1;
                 if new_score > score {
                     score = new_score;
                     coord = Some(m);
                 }
-                if alpha > beta {
+                alpha = alpha.max(score);
+                if alpha >= beta {
+                    self.record_cutoff(game.board(), depth, m);
                     break;
                 }
             }
+            self.store_transposition(key, depth, score, window, coord);
+            (score, coord)
+        }
+    }
+
+    pub fn get_metrics(&self) -> BotMetrics {
+        self.metrics
+    }
+}
+
+/// Writes the bot's settings as a tournament contestant, such as `AlphaBeta:TacticalWeighting:8`.
+impl fmt::Display for Bot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_settings(
+            f,
+            self.algorithm,
+            self.heuristic,
+            self.max_depth,
+            self.time_limit,
+        )
+    }
+}
+
+/// Writes a bot's settings as `ALGORITHM:HEURISTIC[:DEPTH][:TIMEms]`.
+pub(crate) fn write_settings(
+    f: &mut fmt::Formatter<'_>,
+    algorithm: BotAlgorithm,
+    heuristic: BotHeuristic,
+    max_depth: Option<usize>,
+    time_limit: Option<Duration>,
+) -> fmt::Result {
+    write!(f, "{algorithm}:{heuristic}")?;
+    if let Some(depth) = max_depth {
+        write!(f, ":{depth}")?;
+    }
+    if let Some(time_limit) = time_limit {
+        write!(f, ":{}ms", time_limit.as_millis())?;
+    }
+    Ok(())
+}
+
+impl MoveProvider for Bot {
+    fn name(&self) -> String {
+        format!("{} {}", self.algorithm, self.heuristic)
+    }
+
+    fn choose_move(&mut self, position: PositionView<'_>) -> Result<ChosenMove, ProviderError> {
+        let coord = self.try_get_move(position.game().clone())?;
+        Ok(ChosenMove::new(coord).with_analysis(Analysis {
+            depth: Some(self.metrics.depth_reached),
+            metrics: Some(self.metrics),
+            ..Default::default()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{benchmark, history::Move};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_pruning_algorithms_match_minmax_score() {
+        for heuristic in BotHeuristic::iter() {
+            let mut game = Reversi::new(Some((
+                Player::Green,
+                BotDifficulty::Medium,
+                BotAlgorithm::MinMax,
+                heuristic,
+            )));
+            game.update_valid_moves();
+
+            let mut minmax = Bot::new(BotAlgorithm::MinMax, Some(5), heuristic);
+            let mut alphabeta = Bot::new(BotAlgorithm::AlphaBeta, Some(5), heuristic);
+            let mut negamax = Bot::new(BotAlgorithm::NegaMax, Some(5), heuristic);
+            let (score, _) = minmax.minmax(&mut game.clone(), 0);
+
+            assert_eq!(
+                alphabeta
+                    .alphabeta(&mut game.clone(), 0, i64::MIN, i64::MAX)
+                    .0,
+                score
+            );
+            assert_eq!(
+                negamax.negamax(&mut game.clone(), 0, -i64::MAX, i64::MAX).0,
+                score
+            );
+            assert!(alphabeta.get_metrics().expansions < minmax.get_metrics().expansions);
+        }
+    }
+
+    #[test]
+    fn test_iterative_deepening_respects_limits() {
+        let mut game = Reversi::new(Some((
+            Player::Green,
+            BotDifficulty::OneSecond,
+            BotAlgorithm::AlphaBeta,
+            BotHeuristic::UniformWeighting,
+        )));
+        game.update_valid_moves();
+
+        let mut capped = Bot::new(
+            BotAlgorithm::NegaMax,
+            Some(3),
+            BotHeuristic::UniformWeighting,
+        )
+        .with_time_limit(Some(Duration::from_secs(60)));
+        let coord = capped.get_move(game.clone());
+        assert!(game.valid_moves().contains(&coord));
+        assert_eq!(capped.get_metrics().depth_reached, 3);
+
+        let mut hurried = Bot::new(
+            BotAlgorithm::AlphaBeta,
+            None,
+            BotHeuristic::UniformWeighting,
+        )
+        .with_time_limit(Some(Duration::from_millis(1)));
+        let coord = hurried.get_move(game.clone());
+        assert!(game.valid_moves().contains(&coord));
+        assert!(hurried.get_metrics().depth_reached >= 1);
+
+        // far too many empty squares to solve in time, so it searches instead
+        let mut solver = Bot::new(
+            BotAlgorithm::AlphaBeta,
+            None,
+            BotHeuristic::UniformWeighting,
+        )
+        .with_time_limit(Some(Duration::from_millis(200)))
+        .with_endgame_solver(Some((64, EndgameMode::DiscDifferential)));
+        let started = Instant::now();
+        let coord = solver.get_move(game.clone());
+        assert!(game.valid_moves().contains(&coord));
+        assert!(solver.get_metrics().endgame_nodes > 0);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_parallel_matches_negamax_score() {
+        for game in benchmark::positions().into_iter().take(4) {
+            let heuristic = BotHeuristic::TacticalWeighting;
+            let mut negamax = Bot::new(BotAlgorithm::NegaMax, Some(4), heuristic);
+            let (score, _) = negamax.negamax(&mut game.clone(), 0, -i64::MAX, i64::MAX);
+
+            let mut parallel = Bot::new(BotAlgorithm::Parallel, Some(4), heuristic).with_threads(3);
+            let coord = parallel.get_move(game.clone());
+            let mut check = Bot::new(BotAlgorithm::NegaMax, Some(4), heuristic);
+            assert_eq!(
+                check.negamax_after_move(&mut game.clone(), coord, -i64::MAX),
+                score
+            );
+        }
+    }
+
+    #[test]
+    fn test_move_ordering_is_deterministic() {
+        for algorithm in [BotAlgorithm::AlphaBeta, BotAlgorithm::NegaMax] {
+            for shallow_search_ordering in [false, true] {
+                for game in benchmark::positions() {
+                    let new_bot = || {
+                        Bot::new(algorithm, Some(4), BotHeuristic::TacticalWeighting)
+                            .with_shallow_search_ordering(shallow_search_ordering)
+                    };
+                    let (mut first, mut second) = (new_bot(), new_bot());
+                    assert_eq!(first.get_move(game.clone()), second.get_move(game.clone()));
+                    assert_eq!(first.get_metrics(), second.get_metrics());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pvs_and_mtdf_match_negamax_score() {
+        for game in benchmark::positions() {
+            let heuristic = BotHeuristic::TacticalWeighting;
+            let mut negamax = Bot::new(BotAlgorithm::NegaMax, Some(4), heuristic);
+            let (score, _) = negamax.negamax(&mut game.clone(), 0, -i64::MAX, i64::MAX);
+
+            let mut pvs = Bot::new(BotAlgorithm::Pvs, Some(4), heuristic);
+            assert_eq!(pvs.pvs(&mut game.clone(), 0, -i64::MAX, i64::MAX).0, score);
+
+            let mut mtdf = Bot::new(BotAlgorithm::MtdF, Some(4), heuristic);
+            let coord = mtdf.get_move(game.clone());
+            let mut check = Bot::new(BotAlgorithm::NegaMax, Some(4), heuristic);
+            assert_eq!(
+                check.negamax_after_move(&mut game.clone(), coord, -i64::MAX),
+                score
+            );
+        }
+    }
+
+    #[test]
+    fn test_mtdf_chooses_the_alphabeta_move() {
+        for heuristic in [BotHeuristic::TacticalWeighting, BotHeuristic::Pattern] {
+            for game in benchmark::positions() {
+                let mut check = Bot::new(BotAlgorithm::NegaMax, Some(4), heuristic);
+                let scores: Vec<_> = game
+                    .valid_moves()
+                    .iter()
+                    .map(|&m| check.negamax_after_move(&mut game.clone(), m, -i64::MAX))
+                    .collect();
+                let best = *scores.iter().max().unwrap();
+                // with several best moves either search may pick any of them
+                if scores.iter().filter(|&&score| score == best).count() > 1 {
+                    continue;
+                }
+
+                let mut alphabeta = Bot::new(BotAlgorithm::AlphaBeta, Some(4), heuristic);
+                let mut mtdf = Bot::new(BotAlgorithm::MtdF, Some(4), heuristic);
+                assert_eq!(
+                    mtdf.get_move(game.clone()),
+                    alphabeta.get_move(game.clone())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_transpositions_are_reused_by_the_other_side() {
+        for algorithm in [BotAlgorithm::MinMax, BotAlgorithm::AlphaBeta] {
+            for game in benchmark::positions() {
+                let heuristic = BotHeuristic::TacticalWeighting;
+                // searching Green's move fills the table with Red's replies to it, searched to
+                // the depth that they are searched to below
+                let mut bot = Bot::new(algorithm, Some(3), heuristic);
+                let coord = bot.get_move(game.clone());
+                let mut reply = game.clone();
+                reply.make_move(Move::Place(coord));
+                if reply.valid_moves().is_empty() {
+                    continue;
+                }
 
-            (score, coord, (beta, alpha))
+                bot.max_depth = Some(2);
+                let mut check = Bot::new(BotAlgorithm::NegaMax, Some(2), heuristic);
+                let (score, _) = check.negamax(&mut reply.clone(), 0, -i64::MAX, i64::MAX);
+                let coord = bot.get_move(reply.clone());
+                assert_eq!(
+                    check.negamax_after_move(&mut reply.clone(), coord, -i64::MAX),
+                    score
+                );
+            }
         }
     }
 
-    pub fn get_metrics(&self) -> (usize, usize) {
-        (self.expansions, self.comparisons)
+    #[test]
+    fn test_try_get_move_without_legal_moves() {
+        // only Green's pieces are left, so neither player can move
+        let mut board = Board::new(8);
+        for coord in [(3, 4), (4, 3)] {
+            board.set(coord, Some(Player::Green));
+        }
+        let game = Reversi::from_position(board, Player::Red);
+        let mut bot = Bot::new(
+            BotAlgorithm::AlphaBeta,
+            Some(3),
+            BotHeuristic::UniformWeighting,
+        );
+        assert!(matches!(
+            bot.try_get_move(game),
+            Err(Error::NoLegalMoves(Player::Red))
+        ));
     }
 }
@@ -5,5 +5,8 @@ pub enum BotAlgorithm {
     MinMax,
     AlphaBeta,
     NegaMax,
-    Async,
+    Parallel,
+    Pvs,
+    MtdF,
+    Mcts,
 }
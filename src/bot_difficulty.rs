@@ -4,4 +4,7 @@ pub enum BotDifficulty {
     Medium,
     Hard,
     Insane,
+    OneSecond,
+    FiveSeconds,
+    TenSeconds,
 }
@@ -2,4 +2,88 @@
 pub enum BotHeuristic {
     UniformWeighting,
     TacticalWeighting,
+    /// Moves available now.
+    Mobility,
+    /// Empty squares next to opponent discs, i.e. moves likely to become available.
+    PotentialMobility,
+    /// Discs next to empty squares, which hand the opponent moves.
+    Frontier,
+    /// Discs that can never be flipped again.
+    Stability,
+    /// Whether the player to move will get the last move of the game.
+    Parity,
+    /// Learned weights for edge, corner and diagonal patterns, loaded from a weight file or
+    /// built in.
+    Pattern,
+    /// A weighted sum of all of the above but `Pattern`, with separate weights per game phase.
+    Composite(CompositeWeights),
+}
+
+/// How much each feature counts in a [`BotHeuristic::Composite`] evaluation.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct HeuristicWeights {
+    pub discs: i64,
+    pub positional: i64,
+    pub mobility: i64,
+    pub potential_mobility: i64,
+    pub frontier: i64,
+    pub stability: i64,
+    pub parity: i64,
+}
+
+/// [`HeuristicWeights`] for each phase of the game, chosen by how full the board is.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CompositeWeights {
+    /// Used while less than a third of the board is filled.
+    pub opening: HeuristicWeights,
+    pub midgame: HeuristicWeights,
+    /// Used once less than a third of the board is empty.
+    pub endgame: HeuristicWeights,
+}
+
+impl Default for CompositeWeights {
+    fn default() -> Self {
+        Self {
+            opening: HeuristicWeights {
+                discs: 0,
+                positional: 1,
+                mobility: 10,
+                potential_mobility: 5,
+                frontier: 3,
+                stability: 20,
+                parity: 0,
+            },
+            midgame: HeuristicWeights {
+                discs: 1,
+                positional: 1,
+                mobility: 8,
+                potential_mobility: 4,
+                frontier: 3,
+                stability: 25,
+                parity: 5,
+            },
+            endgame: HeuristicWeights {
+                discs: 5,
+                positional: 1,
+                mobility: 4,
+                potential_mobility: 1,
+                frontier: 1,
+                stability: 30,
+                parity: 20,
+            },
+        }
+    }
+}
+
+impl CompositeWeights {
+    /// The weights for a position with `empties` of `squares` squares still empty.
+    pub fn for_phase(&self, empties: usize, squares: usize) -> &HeuristicWeights {
+        if 3 * empties > 2 * squares {
+            &self.opening
+        } else if 3 * empties < squares {
+            &self.endgame
+        } else {
+            &self.midgame
+        }
+    }
 }
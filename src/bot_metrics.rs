@@ -0,0 +1,50 @@
+use std::fmt;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct BotMetrics {
+    pub expansions: usize,
+    pub comparisons: usize,
+    pub transposition_hits: usize,
+    pub depth_reached: usize,
+    pub endgame_nodes: usize,
+    pub playouts: usize,
+    pub playout_moves: usize,
+    pub tree_size: usize,
+}
+
+impl BotMetrics {
+    /// Positions the search visited, whether by expanding them, solving them or playing
+    /// through them in a playout.
+    pub fn nodes(&self) -> usize {
+        self.expansions + self.endgame_nodes + self.playout_moves
+    }
+
+    /// Adds the node counters from another search, such as a worker thread's.
+    pub fn merge(&mut self, other: BotMetrics) {
+        self.expansions += other.expansions;
+        self.comparisons += other.comparisons;
+        self.transposition_hits += other.transposition_hits;
+        self.endgame_nodes += other.endgame_nodes;
+        self.playouts += other.playouts;
+        self.playout_moves += other.playout_moves;
+        self.tree_size += other.tree_size;
+    }
+}
+
+impl fmt::Display for BotMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Expansions: {} | Comparisons: {} | TT hits: {} | Depth: {}\n\
+            Endgame nodes: {} | Playouts: {} ({} moves) | Tree size: {}",
+            self.expansions,
+            self.comparisons,
+            self.transposition_hits,
+            self.depth_reached,
+            self.endgame_nodes,
+            self.playouts,
+            self.playout_moves,
+            self.tree_size
+        )
+    }
+}
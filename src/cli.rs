@@ -0,0 +1,400 @@
+//! The command line of the main binary, for scripting the engine without prompts.
+
+use std::{fmt::Display, time::Duration};
+
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use strum::IntoEnumIterator;
+use web_time::Instant;
+
+use crate::{
+    benchmark,
+    bot_algorithm::BotAlgorithm,
+    bot_heuristic::BotHeuristic,
+    endgame_solver::{self, EndgameMode},
+    history::Move,
+    perft,
+    player::Player,
+    reversi::Reversi,
+    tournament::Contestant,
+    transcript::{self, Transcript},
+};
+
+pub const USAGE: &str = "usage: kar_reversi_minmax [COMMAND] [OPTIONS]
+
+commands:
+  play [--colour green|red|both|none] [--size N] [--position POSITION] [BOT OPTIONS]
+      play in the terminal as the colour given against the bot, or asking who plays each
+      side without a colour or bot options, full screen unless input or output is piped
+  analyze POSITION [BOT OPTIONS]
+      print the bot's move in POSITION
+  perft [--divide] [--position POSITION] DEPTH
+      count the lines of play to each depth up to DEPTH
+  bench [BOT OPTIONS]
+      time the bot on the benchmark positions
+  selfplay [--games N] [--seed N] [--size N] [--position POSITION] [BOT OPTIONS]
+      print transcripts of games the bot plays against itself
+  solve POSITION [--win-loss-draw]
+      print the best move in POSITION and the final disc difference it leads to
+
+bot options:
+  --algorithm ALGORITHM  (default AlphaBeta)
+  --heuristic HEURISTIC  (default TacticalWeighting)
+  --depth N              (default 6 unless there is a time limit)
+  --time MILLISECONDS
+
+Positions are the size×size squares from a1 on (a1 to h8 on 8x8) as X, O or -, then the player
+to move as X or O.
+Without a command the game asks who plays each side, as `play` does.";
+
+/// The settings of `play`, `analyze`, `bench` and `selfplay`.
+#[derive(Clone, PartialEq, Debug)]
+struct Options {
+    bot: Contestant,
+    /// Whether a human plays Green and whether one plays Red in `play`, or `None` to ask.
+    humans: Option<(bool, bool)>,
+    size: Option<usize>,
+    position: Option<String>,
+    games: usize,
+    seed: u64,
+    divide: bool,
+    win_loss_draw: bool,
+    /// Arguments that are not options, such as a position or depth.
+    operands: Vec<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            bot: Contestant {
+                algorithm: BotAlgorithm::AlphaBeta,
+                heuristic: BotHeuristic::TacticalWeighting,
+                max_depth: None,
+                time_limit: None,
+            },
+            humans: None,
+            size: None,
+            position: None,
+            games: 1,
+            seed: 0,
+            divide: false,
+            win_loss_draw: false,
+            operands: Vec::new(),
+        }
+    }
+}
+
+/// Runs the command in `args`, the program's arguments without its name. Options without a
+/// command play a game, so `--position POSITION` alone starts from it.
+pub fn run(args: Vec<String>) -> Result<(), String> {
+    let (command, args) = match args.split_first() {
+        Some((command, rest)) if !is_option(command) => (command.as_str(), rest),
+        _ => ("play", &args[..]),
+    };
+    if command == "help" || args.iter().any(|arg| arg == "--help" || arg == "-h") {
+        println!("{USAGE}");
+        return Ok(());
+    }
+    let options = parse_options(args).map_err(|error| format!("{error}\n\n{USAGE}"))?;
+
+    // these take no operands, so anything left over is a mistyped option
+    if let (Some(arg), "play" | "bench" | "selfplay") = (options.operands.first(), command) {
+        return Err(format!("unexpected argument '{arg}'\n\n{USAGE}"));
+    }
+    match command {
+        "play" => play(&options),
+        "analyze" => analyze(&options),
+        "perft" => run_perft(&options),
+        "bench" => bench(&options),
+        "selfplay" => self_play(&options),
+        "solve" => solve(&options),
+        _ => Err(format!("unknown command '{command}'\n\n{USAGE}")),
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut bot_given = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !is_option(arg) {
+            options.operands.push(arg.clone());
+            continue;
+        }
+        bot_given |= ["--algorithm", "--heuristic", "--depth", "--time"].contains(&arg.as_str());
+        let mut value = || args.next().ok_or(format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--algorithm" => options.bot.algorithm = parse_variant("algorithm", value()?)?,
+            "--heuristic" => options.bot.heuristic = parse_variant("heuristic", value()?)?,
+            "--depth" => options.bot.max_depth = Some(parse_number("depth", value()?)?),
+            "--time" => {
+                let millis = parse_number("time limit", value()?)?;
+                options.bot.time_limit = Some(Duration::from_millis(millis));
+            }
+            "--colour" | "--color" => {
+                options.humans = Some(match value()?.to_ascii_lowercase().as_str() {
+                    "green" => (true, false),
+                    "red" => (false, true),
+                    "both" => (true, true),
+                    "none" => (false, false),
+                    colour => return Err(format!("unknown colour '{colour}'")),
+                })
+            }
+            "--size" => options.size = Some(parse_number("board size", value()?)?),
+            "--position" => options.position = Some(value()?.clone()),
+            "--games" => options.games = parse_number("number of games", value()?)?,
+            "--seed" => options.seed = parse_number("seed", value()?)?,
+            "--divide" => options.divide = true,
+            "--win-loss-draw" => options.win_loss_draw = true,
+            _ => {}
+        }
+    }
+    // a bot given without a colour plays Red
+    if bot_given && options.humans.is_none() {
+        options.humans = Some((true, false));
+    }
+    options.bot = options.bot.with_default_limit();
+    Ok(options)
+}
+
+/// Every option the commands take. Anything else is an operand, since positions can start
+/// with `--` too.
+const OPTIONS: [&str; 14] = [
+    "--algorithm",
+    "--heuristic",
+    "--depth",
+    "--time",
+    "--colour",
+    "--color",
+    "--size",
+    "--position",
+    "--games",
+    "--seed",
+    "--divide",
+    "--win-loss-draw",
+    "--help",
+    "-h",
+];
+
+fn is_option(arg: &str) -> bool {
+    OPTIONS.contains(&arg)
+}
+
+/// One of `T`'s variants by name, ignoring case.
+fn parse_variant<T: IntoEnumIterator + Display>(kind: &str, name: &str) -> Result<T, String> {
+    T::iter()
+        .find(|variant| variant.to_string().eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            let names: Vec<_> = T::iter().map(|variant| variant.to_string()).collect();
+            format!("unknown {kind} '{name}' (use one of {})", names.join(", "))
+        })
+}
+
+fn parse_number<T: std::str::FromStr>(kind: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid {kind} '{value}'"))
+}
+
+/// The game to start from: the position if one was given, else the start on the board size
+/// given, with humans on both sides.
+fn starting_game(options: &Options) -> Result<Reversi, String> {
+    match (&options.position, options.size) {
+        (Some(position), _) => Reversi::from_position_str(position).map_err(|e| e.to_string()),
+        (None, size) => Reversi::new(None)
+            .try_with_board_size(size.unwrap_or(8))
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// The one position operand of `analyze` and `solve`.
+fn position_operand(options: &Options) -> Result<Reversi, String> {
+    let position = options.operands.join(" ");
+    if position.is_empty() {
+        return Err(format!("a position is needed\n\n{USAGE}"));
+    }
+    Reversi::from_position_str(&position).map_err(|e| e.to_string())
+}
+
+#[cfg(feature = "terminal")]
+fn play(options: &Options) -> Result<(), String> {
+    use crate::terminal;
+
+    let Some((green_human, red_human)) = options.humans else {
+        if options.position.is_none() && options.size.is_none() {
+            terminal::run(Vec::new());
+        } else {
+            terminal::run_from(Vec::new(), starting_game(options)?);
+        }
+        return Ok(());
+    };
+
+    let mut game = starting_game(options)?;
+    for (player, human) in [(Player::Green, green_human), (Player::Red, red_human)] {
+        if !human {
+            *game.controller_mut(player) = options.bot.bot().into();
+        }
+    }
+    terminal::play(game);
+    Ok(())
+}
+
+#[cfg(not(feature = "terminal"))]
+fn play(_: &Options) -> Result<(), String> {
+    Err(String::from("play needs the terminal feature"))
+}
+
+fn analyze(options: &Options) -> Result<(), String> {
+    let game = position_operand(options)?;
+    let mut bot = options.bot.bot();
+    let start = Instant::now();
+    let coord = bot.try_get_move(game.clone()).map_err(|e| e.to_string())?;
+    println!("{}", transcript::square_name(coord, game.board().size()));
+    eprintln!(
+        "{} in {:.3}s\n{}",
+        options.bot,
+        start.elapsed().as_secs_f64(),
+        bot.get_metrics()
+    );
+    Ok(())
+}
+
+fn run_perft(options: &Options) -> Result<(), String> {
+    let game = starting_game(options)?;
+    let depth: usize = match &options.operands[..] {
+        [depth] => parse_number("depth", depth)?,
+        _ => return Err(format!("a depth is needed\n\n{USAGE}")),
+    };
+
+    if options.divide {
+        let start = Instant::now();
+        let mut total = 0;
+        for (m, nodes) in perft::divide(&game, depth) {
+            let name = match m {
+                Move::Place(coord) => transcript::square_name(coord, game.board().size()),
+                Move::Pass => String::from("pa"),
+            };
+            println!("{name}: {nodes}");
+            total += nodes;
+        }
+        println!("\nTotal: {total} in {:.3}s", start.elapsed().as_secs_f64());
+        return Ok(());
+    }
+
+    for depth in 1..=depth {
+        let start = Instant::now();
+        let nodes = perft::perft(&game, depth);
+        let elapsed = start.elapsed().as_secs_f64();
+        println!(
+            "Depth {depth}: {nodes} in {elapsed:.3}s ({:.0} nodes/s)",
+            nodes as f64 / elapsed.max(1e-9)
+        );
+    }
+    Ok(())
+}
+
+fn bench(options: &Options) -> Result<(), String> {
+    let result = benchmark::run(&options.bot.bot());
+    println!(
+        "{}: {} positions in {:.3}s\n{}",
+        options.bot,
+        result.positions,
+        result.elapsed.as_secs_f64(),
+        result.metrics
+    );
+    Ok(())
+}
+
+/// Moves played at random at the start of each `selfplay` game, so that the games differ.
+/// Fewer than the tuner's training games open with, so that the openings stay close to even
+/// and the games say something about the bot.
+const SELF_PLAY_RANDOM_PLIES: usize = 4;
+
+fn self_play(options: &Options) -> Result<(), String> {
+    let mut rng = SmallRng::seed_from_u64(options.seed);
+    let mut wins = [0; 2];
+    let mut draws = 0;
+    for _ in 0..options.games {
+        let mut game = starting_game(options)?;
+        *game.controller_mut(Player::Green) = options.bot.bot().into();
+        *game.controller_mut(Player::Red) = options.bot.bot().into();
+        for _ in 0..SELF_PLAY_RANDOM_PLIES {
+            let moves = game.legal_moves();
+            if moves.is_empty() {
+                break;
+            }
+            game.play(moves[rng.gen_range(0..moves.len())]).unwrap();
+        }
+        match game.play_to_end().map_err(|e| e.to_string())? {
+            Some(winner) => wins[winner as usize] += 1,
+            None => draws += 1,
+        }
+        println!("{}", Transcript::from_game(&game));
+    }
+    eprintln!(
+        "{} won {}, {} won {}, {draws} drawn",
+        Player::Green,
+        wins[0],
+        Player::Red,
+        wins[1]
+    );
+    Ok(())
+}
+
+fn solve(options: &Options) -> Result<(), String> {
+    let game = position_operand(options)?;
+    let mode = if options.win_loss_draw {
+        EndgameMode::WinLossDraw
+    } else {
+        EndgameMode::DiscDifferential
+    };
+    let start = Instant::now();
+    let (coord, score, nodes) = endgame_solver::solve_position(&game, mode)
+        .ok_or(format!("{} has no legal move", game.current_player()))?;
+    println!(
+        "{} {score:+}",
+        transcript::square_name(coord, game.board().size())
+    );
+    eprintln!("{nodes} positions in {:.3}s", start.elapsed().as_secs_f64());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_options() {
+        let options = parse_options(&args(
+            "--algorithm negamax --heuristic PATTERN --time 500 --colour red",
+        ))
+        .unwrap();
+        assert_eq!(options.bot.to_string(), "NegaMax:Pattern:500ms");
+        assert_eq!(options.humans, Some((false, true)));
+
+        let options = parse_options(&args("--depth 3 --OX X")).unwrap();
+        assert_eq!(options.bot.to_string(), "AlphaBeta:TacticalWeighting:3");
+        assert_eq!(options.operands, vec!["--OX", "X"]);
+        assert_eq!(options.humans, Some((true, false)));
+        assert_eq!(parse_options(&args("--size 6")).unwrap().humans, None);
+        let lower_case = parse_options(&args("--ox-- --x------ x")).unwrap();
+        assert_eq!(lower_case.operands, vec!["--ox--", "--x------", "x"]);
+
+        assert_eq!(
+            parse_options(&args("--depth")),
+            Err(String::from("--depth needs a value"))
+        );
+        assert!(parse_options(&args("--algorithm Fast"))
+            .unwrap_err()
+            .starts_with("unknown algorithm 'Fast' (use one of MinMax, AlphaBeta"));
+        assert!(run(args("dance")).is_err());
+        assert!(run(args("play --frobnicate"))
+            .unwrap_err()
+            .starts_with("unexpected argument '--frobnicate'"));
+        assert!(run(args("perft 4")).is_ok());
+        assert!(run(args(&format!("solve {}-OXXXXXX X", "-".repeat(56)))).is_ok());
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::{
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    bot::Bot,
+    move_provider::{ChosenMove, MoveProvider, PositionView, ProviderError, SharedMoveProvider},
+    reversi::Reversi,
+    transcript,
+};
+
+/// How long an engine is given to answer unless [`ExternalEngine::with_timeout`] says
+/// otherwise.
+const DEFAULT_ENGINE_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumIter, strum::EnumString, Debug)]
+pub enum ControllerKind {
+    Human,
+    Bot,
+    Engine,
+    Custom,
+}
+
+/// Who chooses the moves for one side of a game.
+#[derive(Clone, Default, Debug)]
+pub enum Controller {
+    /// Moves are entered through the front end.
+    #[default]
+    Human,
+    Bot(Box<Bot>),
+    Engine(ExternalEngine),
+    /// A [`MoveProvider`] from outside the crate.
+    Custom(SharedMoveProvider),
+}
+
+impl Controller {
+    pub fn kind(&self) -> ControllerKind {
+        match self {
+            Self::Human => ControllerKind::Human,
+            Self::Bot(_) => ControllerKind::Bot,
+            Self::Engine(_) => ControllerKind::Engine,
+            Self::Custom(_) => ControllerKind::Custom,
+        }
+    }
+
+    pub fn is_human(&self) -> bool {
+        matches!(self, Self::Human)
+    }
+
+    pub fn as_bot(&self) -> Option<&Bot> {
+        match self {
+            Self::Bot(bot) => Some(bot),
+            _ => None,
+        }
+    }
+
+    pub fn as_bot_mut(&mut self) -> Option<&mut Bot> {
+        match self {
+            Self::Bot(bot) => Some(bot),
+            _ => None,
+        }
+    }
+
+    /// The move this controller chooses in `position`, or `None` for a human.
+    pub(crate) fn choose_move(
+        &mut self,
+        position: PositionView<'_>,
+    ) -> Option<Result<ChosenMove, ProviderError>> {
+        match self {
+            Self::Human => None,
+            Self::Bot(bot) => Some(bot.choose_move(position)),
+            Self::Engine(engine) => Some(engine.choose_move(position)),
+            Self::Custom(provider) => Some(provider.lock().unwrap().choose_move(position)),
+        }
+    }
+}
+
+/// Writes who plays, such as `Human` or `Bot AlphaBeta:TacticalWeighting:8`.
+impl fmt::Display for Controller {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Human => write!(f, "Human"),
+            Self::Bot(bot) => write!(f, "Bot {bot}"),
+            Self::Engine(engine) => write!(f, "Engine {}", engine.name()),
+            Self::Custom(provider) => write!(f, "Custom {}", provider.lock().unwrap().name()),
+        }
+    }
+}
+
+impl From<Bot> for Controller {
+    fn from(bot: Bot) -> Self {
+        Self::Bot(Box::new(bot))
+    }
+}
+
+impl From<Box<dyn MoveProvider>> for Controller {
+    fn from(provider: Box<dyn MoveProvider>) -> Self {
+        Self::Custom(Arc::new(Mutex::new(provider)))
+    }
+}
+
+/// A program in another process that plays one side of the game.
+///
+/// For every move it is sent the position as a line in the format of
+/// [`Reversi::to_position_string`]: the squares from a1 to h8 as `X` (Green), `O` (Red) or
+/// `-`, a space and the player to move as `X` or `O`. It must answer with a line holding its
+/// move as a square such as `d3`, named as in [`transcript`]. It is only asked when it has a
+/// legal move, and fails to move if it takes longer than its timeout, after which it is
+/// restarted.
+#[derive(Clone)]
+pub struct ExternalEngine {
+    program: String,
+    args: Vec<String>,
+    command: String,
+    timeout: Option<Duration>,
+    process: Arc<Mutex<EngineProcess>>,
+}
+
+struct EngineProcess {
+    child: Child,
+    stdin: ChildStdin,
+    /// Lines from the engine, read on a thread of their own so that waiting for them can
+    /// time out.
+    replies: Receiver<String>,
+}
+
+impl EngineProcess {
+    fn start(program: &str, args: &[String]) -> io::Result<Self> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let (sender, replies) = mpsc::channel();
+        thread::spawn(move || {
+            for line in stdout.lines() {
+                if line.ok().is_none_or(|line| sender.send(line).is_err()) {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            stdin,
+            replies,
+        })
+    }
+
+    /// Replaces the engine with a fresh copy, so that an answer it was still working on
+    /// cannot be read as the answer to a later position. An engine that fails to start
+    /// again reads as exited.
+    fn restart(&mut self, program: &str, args: &[String]) {
+        match Self::start(program, args) {
+            Ok(process) => *self = process,
+            Err(_) => {
+                self.child.kill().ok();
+                self.child.wait().ok();
+                self.replies = mpsc::channel().1;
+            }
+        }
+    }
+}
+
+impl Drop for EngineProcess {
+    fn drop(&mut self) {
+        self.child.kill().ok();
+        self.child.wait().ok();
+    }
+}
+
+impl fmt::Debug for ExternalEngine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExternalEngine")
+            .field("command", &self.command)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExternalEngine {
+    /// Starts `program` with `args`, talking to it over its standard input and output.
+    pub fn spawn(program: &str, args: &[&str]) -> io::Result<Self> {
+        let args: Vec<String> = args.iter().map(ToString::to_string).collect();
+        let process = EngineProcess::start(program, &args)?;
+        Ok(Self {
+            command: [program]
+                .into_iter()
+                .chain(args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" "),
+            program: program.to_string(),
+            args,
+            timeout: Some(DEFAULT_ENGINE_TIMEOUT),
+            process: Arc::new(Mutex::new(process)),
+        })
+    }
+
+    /// Sets how long the engine may take to answer, or lets it take as long as it likes.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Asks the engine for its move in `game`, which must be a legal one.
+    pub fn get_move(&self, game: &Reversi) -> io::Result<(usize, usize)> {
+        let mut process = self.process.lock().unwrap();
+        writeln!(process.stdin, "{}", game.to_position_string())?;
+        process.stdin.flush()?;
+
+        let reply = match self.timeout {
+            Some(timeout) => process.replies.recv_timeout(timeout),
+            None => process
+                .replies
+                .recv()
+                .map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        let reply = reply.map_err(|error| match error {
+            RecvTimeoutError::Timeout => {
+                process.restart(&self.program, &self.args);
+                io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("engine '{}' did not answer in time", self.command),
+                )
+            }
+            RecvTimeoutError::Disconnected => io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("engine '{}' exited", self.command),
+            ),
+        })?;
+        let invalid = |reason: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("engine '{}' {reason}: '{}'", self.command, reply.trim_end()),
+            )
+        };
+        let coord = transcript::parse_square(reply.trim(), game.board().size())
+            .ok_or_else(|| invalid("sent an unreadable move"))?;
+        if !game.valid_moves().contains(&coord) {
+            return Err(invalid("sent an illegal move"));
+        }
+        Ok(coord)
+    }
+}
+
+impl MoveProvider for ExternalEngine {
+    fn name(&self) -> String {
+        self.command.clone()
+    }
+
+    fn choose_move(&mut self, position: PositionView<'_>) -> Result<ChosenMove, ProviderError> {
+        Ok(ChosenMove::new(self.get_move(position.game())?))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_external_engine_moves_are_checked() {
+        let mut game = Reversi::with_controllers(Controller::Human, Controller::Human);
+        game.update_valid_moves();
+
+        let engine = ExternalEngine::spawn("sh", &["-c", "read position; echo e6"]).unwrap();
+        assert_eq!(engine.get_move(&game).unwrap(), (2, 4));
+
+        for reply in ["a1", "3 5"] {
+            let script = format!("read position; echo {reply}");
+            let engine = ExternalEngine::spawn("sh", &["-c", &script]).unwrap();
+            assert_eq!(
+                engine.get_move(&game).unwrap_err().kind(),
+                io::ErrorKind::InvalidData
+            );
+        }
+
+        let engine = ExternalEngine::spawn("sh", &["-c", "exit 0"]).unwrap();
+        assert!(engine.get_move(&game).is_err());
+
+        // the engine only stalls the first time it runs, so the second move is answered
+        // by the copy started after the timeout
+        let marker = std::env::temp_dir().join(format!("engine-timeout-{}", std::process::id()));
+        let script = format!(
+            "if [ -e '{0}' ]; then read position; echo c4; \
+             else touch '{0}'; read position; sleep 5; echo e6; fi",
+            marker.display()
+        );
+        let engine = ExternalEngine::spawn("sh", &["-c", &script])
+            .unwrap()
+            .with_timeout(Some(Duration::from_millis(500)));
+        assert_eq!(
+            engine.get_move(&game).unwrap_err().kind(),
+            io::ErrorKind::TimedOut
+        );
+        assert_eq!(engine.get_move(&game).unwrap(), (4, 2));
+        std::fs::remove_file(marker).ok();
+    }
+}
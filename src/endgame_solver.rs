@@ -0,0 +1,192 @@
+use web_time::Instant;
+
+use crate::{board::Board, player::Player, reversi::Reversi};
+
+/// How many positions are searched between looks at the clock.
+const DEADLINE_CHECK_INTERVAL: usize = 4096;
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumIter, strum::EnumString, Debug)]
+pub enum EndgameMode {
+    /// Only distinguishes wins, draws and losses, which prunes far more of the tree.
+    WinLossDraw,
+    /// Maximises the final difference in piece counts.
+    DiscDifferential,
+}
+
+/// Searches to the end of the game and returns `player`'s best move with its exact score:
+/// the final piece difference, or just its sign in [`EndgameMode::WinLossDraw`]. Gives
+/// `None` if `player` has no legal move or the search is still running at `deadline`.
+pub(crate) fn best_move(
+    board: &Board,
+    player: Player,
+    mode: EndgameMode,
+    deadline: Option<Instant>,
+    nodes: &mut usize,
+) -> Option<((usize, usize), i64)> {
+    let (mut alpha, beta) = match mode {
+        EndgameMode::WinLossDraw => (-1, 1),
+        EndgameMode::DiscDifferential => (-i64::MAX, i64::MAX),
+    };
+
+    let mut board = board.clone();
+    let mut best = None;
+    for coord in ordered_moves(&board, player) {
+        let flips = board.flips_for_move(coord, player);
+        board.apply_move(coord, player, flips);
+        let score = -solve(
+            &mut board,
+            player.other(),
+            -beta,
+            -alpha,
+            false,
+            deadline,
+            nodes,
+        )?;
+        board.undo_move(coord, player, flips);
+
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((coord, score));
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+    }
+
+    best.map(|(coord, score)| match mode {
+        EndgameMode::WinLossDraw => (coord, score.signum()),
+        EndgameMode::DiscDifferential => (coord, score),
+    })
+}
+
+/// The best move for the player to move in `game`, its exact score as [`best_move`] gives it
+/// and the number of positions searched, or `None` if the player has no legal move. Only
+/// practical with about 20 empty squares or fewer.
+pub fn solve_position(game: &Reversi, mode: EndgameMode) -> Option<((usize, usize), i64, usize)> {
+    let mut nodes = 0;
+    let (coord, score) = best_move(game.board(), game.current_player(), mode, None, &mut nodes)?;
+    Some((coord, score, nodes))
+}
+
+/// Exact negamax score of the position for `player`, who moves next, or `None` once
+/// `deadline` has passed. `passed` is set when the previous player had to pass, so a second
+/// pass ends the game.
+pub(crate) fn solve(
+    board: &mut Board,
+    player: Player,
+    mut alpha: i64,
+    beta: i64,
+    passed: bool,
+    deadline: Option<Instant>,
+    nodes: &mut usize,
+) -> Option<i64> {
+    *nodes += 1;
+    if nodes.is_multiple_of(DEADLINE_CHECK_INTERVAL)
+        && deadline.is_some_and(|d| Instant::now() >= d)
+    {
+        return None;
+    }
+
+    if board.valid_moves_bits(player) == 0 {
+        if passed {
+            return Some(
+                board.count_for_player(player) as i64
+                    - board.count_for_player(player.other()) as i64,
+            );
+        }
+        return Some(-solve(
+            board,
+            player.other(),
+            -beta,
+            -alpha,
+            true,
+            deadline,
+            nodes,
+        )?);
+    }
+
+    let mut best = -i64::MAX;
+    for coord in ordered_moves(board, player) {
+        let flips = board.flips_for_move(coord, player);
+        board.apply_move(coord, player, flips);
+        let score = -solve(board, player.other(), -beta, -alpha, false, deadline, nodes)?;
+        board.undo_move(coord, player, flips);
+
+        if score > best {
+            best = score;
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+    }
+    Some(best)
+}
+
+/// Moves that leave the opponent the fewest replies come first, which finds cutoffs early.
+/// Near the very end ordering costs more than it saves, so moves are left in board order.
+fn ordered_moves(board: &Board, player: Player) -> Vec<(usize, usize)> {
+    let mut moves: Vec<_> = board.coords(board.valid_moves_bits(player)).collect();
+    if board.empty_bits().count_ones() > 6 {
+        let mut board = board.clone();
+        moves.sort_by_cached_key(|&coord| {
+            let flips = board.flips_for_move(coord, player);
+            board.apply_move(coord, player, flips);
+            let replies = board.valid_moves_bits(player.other()).count_ones();
+            board.undo_move(coord, player, flips);
+            replies
+        });
+    }
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::Bot, bot_algorithm::BotAlgorithm, bot_difficulty::BotDifficulty,
+        bot_heuristic::BotHeuristic,
+    };
+
+    #[test]
+    fn test_solver_matches_full_depth_negamax() {
+        let mut game = Reversi::new(Some((
+            Player::Green,
+            BotDifficulty::Easy,
+            BotAlgorithm::NegaMax,
+            BotHeuristic::UniformWeighting,
+        )));
+        game.update_valid_moves();
+        while game.board().empty_bits().count_ones() > 9 && Reversi::anyone_can_move(game.board()) {
+            if let Some(&coord) = game.valid_moves().last() {
+                game.place_piece_and_add_history(coord);
+            }
+            game.switch_players();
+            game.update_valid_moves();
+        }
+
+        let mut nodes = 0;
+        let (_, score) = best_move(
+            game.board(),
+            game.current_player(),
+            EndgameMode::DiscDifferential,
+            None,
+            &mut nodes,
+        )
+        .unwrap();
+        let mut bot = Bot::new(BotAlgorithm::NegaMax, None, BotHeuristic::UniformWeighting);
+        let (expected, _) = bot.negamax(&mut game.clone(), 0, -i64::MAX, i64::MAX);
+        assert_eq!(score, expected);
+        assert!(nodes > 0);
+
+        let (_, outcome) = best_move(
+            game.board(),
+            game.current_player(),
+            EndgameMode::WinLossDraw,
+            None,
+            &mut nodes,
+        )
+        .unwrap();
+        assert_eq!(outcome, expected.signum());
+    }
+}
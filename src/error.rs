@@ -0,0 +1,57 @@
+use std::fmt;
+
+use crate::{move_provider::ProviderError, player::Player, reversi::MoveError};
+
+/// What can go wrong in the engine's entry points, for front ends to report instead of crashing.
+#[derive(Debug)]
+pub enum Error {
+    /// Boards have an even number of squares along each side, from 6 to 10.
+    InvalidBoardSize(usize),
+    /// A position string could not be read, for the reason given.
+    InvalidPosition(String),
+    /// A move was asked of a player without one.
+    NoLegalMoves(Player),
+    /// A game was left to its controllers but this player is a human.
+    HumanToMove(Player),
+    Move(MoveError),
+    /// A bot, engine or other move provider failed to choose a move.
+    Controller(ProviderError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidBoardSize(size) => write!(
+                f,
+                "a board {size} squares wide is not supported (use an even size from 6 to 10)"
+            ),
+            Self::InvalidPosition(reason) => write!(f, "invalid position: {reason}"),
+            Self::NoLegalMoves(player) => write!(f, "{player} has no legal move"),
+            Self::HumanToMove(player) => write!(f, "{player} is played by a human"),
+            Self::Move(error) => write!(f, "{error}"),
+            Self::Controller(error) => write!(f, "the controller failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Move(error) => Some(error),
+            Self::Controller(error) => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<MoveError> for Error {
+    fn from(error: MoveError) -> Self {
+        Self::Move(error)
+    }
+}
+
+impl From<ProviderError> for Error {
+    fn from(error: ProviderError) -> Self {
+        Self::Controller(error)
+    }
+}
@@ -1,27 +1,33 @@
+use std::sync::{Arc, Mutex};
+
 use bevy::prelude::*;
 use bevy_mod_picking::{debug::DebugPickingPlugin, DefaultPickingPlugins};
 
-use crate::game::{
-    interactions::{
-        bot_delay_reset, bot_make_move, maintain_score_display, update_current_player,
-        update_player_scores,
+use crate::{
+    game::{
+        interactions::{
+            bot_delay_reset, bot_make_move, maintain_score_display, update_current_player,
+            update_player_scores,
+        },
+        menu_interactions::handle_difficulty_buttons,
+        /* interactions::highlight_valid_grid_squares, */ pieces::draw_pieces,
+        scenes::{board_setup, menu_setup, menu_teardown},
+        states::GameState,
+        structs::{BevyAiDelay, BevyMoveProviders, BevyReversi},
     },
-    menu_interactions::handle_difficulty_buttons,
-    /* interactions::highlight_valid_grid_squares, */ pieces::draw_pieces,
-    scenes::{board_setup, menu_setup, menu_teardown},
-    states::GameState,
-    structs::{BevyAiDelay, BevyReversi},
+    move_provider::MoveProvider,
 };
 
 use super::{
-    interactions::{display_metrics, show_game_over, update_grid_highlights},
+    interactions::{navigate_history, save_or_load_game, show_game_over, update_grid_highlights},
     menu_interactions::{
-        handle_algorithm_buttons, handle_gamemode_buttons, handle_heuristic_buttons,
-        handle_play_button,
+        handle_algorithm_buttons, handle_board_size_buttons, handle_gamemode_buttons,
+        handle_heuristic_buttons, handle_play_button, handle_provider_buttons,
     },
 };
 
-pub fn run_game() {
+/// Opens the game window, offering `providers` in the menu alongside the built-in bot.
+pub fn run_game(providers: Vec<Box<dyn MoveProvider>>) {
     App::new()
         .add_plugins((
             DefaultPlugins,
@@ -32,6 +38,12 @@ pub fn run_game() {
         .init_resource::<BevyReversi>()
         .init_resource::<Time>()
         .init_resource::<BevyAiDelay>()
+        .insert_resource(BevyMoveProviders(
+            providers
+                .into_iter()
+                .map(|provider| Arc::new(Mutex::new(provider)))
+                .collect(),
+        ))
         .add_state::<GameState>()
         // menu
         .add_systems(OnEnter(GameState::Menu), menu_setup)
@@ -39,6 +51,8 @@ pub fn run_game() {
             Update,
             (
                 handle_gamemode_buttons,
+                handle_board_size_buttons,
+                handle_provider_buttons,
                 handle_difficulty_buttons,
                 handle_algorithm_buttons,
                 handle_heuristic_buttons,
@@ -55,6 +69,13 @@ pub fn run_game() {
             },
             board_setup,
         )
+        .add_systems(
+            OnTransition {
+                from: GameState::Menu,
+                to: GameState::AiTurn,
+            },
+            board_setup,
+        )
         .add_systems(
             Update,
             (
@@ -74,7 +95,6 @@ pub fn run_game() {
                     .run_if(in_state(GameState::AiTurn)),
             ),
         )
-        .add_systems(OnExit(GameState::AiTurn), display_metrics)
         .add_systems(
             PostUpdate,
             (
@@ -82,6 +102,15 @@ pub fn run_game() {
                 update_grid_highlights.run_if(in_state(GameState::AiTurn)),
             ),
         )
+        .add_systems(
+            Update,
+            (
+                navigate_history.run_if(in_state(GameState::PlayerTurn)),
+                navigate_history.run_if(in_state(GameState::AiTurn)),
+                save_or_load_game.run_if(in_state(GameState::PlayerTurn)),
+                save_or_load_game.run_if(in_state(GameState::AiTurn)),
+            ),
+        )
         .add_systems(OnEnter(GameState::AiTurn), bot_delay_reset)
         .add_systems(Update, bot_make_move.run_if(in_state(GameState::AiTurn)))
         .add_systems(OnEnter(GameState::End), show_game_over)
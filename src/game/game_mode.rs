@@ -2,4 +2,6 @@
 pub enum GameMode {
     PlayerVsAi,
     PlayerVsPlayer,
+    /// Two bots play each other while the user watches.
+    AiVsAi,
 }
@@ -15,12 +15,21 @@ use crate::{
         structs::{BevyAiDelay, BevyCurrentPlayer, BevyPlayerScore, BevyReversi},
         utils::*,
     },
+    history::Move,
+    move_provider::Analysis,
     reversi::Reversi,
+    transcript::Transcript,
 };
 
 use crate::player::Player;
 
-use super::structs::{BevyMetricsDisplay, BevySquare};
+use super::{
+    highlight_constants::{BUTTON_DEFAULT, BUTTON_HOVERED},
+    structs::{
+        BevyHistoryButton, BevyMetricsDisplay, BevySquare, BevyTranscriptButton, HistoryStep,
+        TranscriptAction,
+    },
+};
 
 pub fn update_grid_highlights(
     mut commands: Commands,
@@ -60,7 +69,7 @@ pub fn update_grid_highlights(
         for (entity, transform, initial_highlight) in &query {
             let mut entity = commands.entity(entity);
             let Vec3 { x, z, .. } = transform.translation;
-            let coord = game_coord_to_reversi_coord((x, z));
+            let coord = game_coord_to_reversi_coord((x, z), game.0.board().size());
             if game.0.valid_moves().contains(&coord) {
                 entity.insert(PickHighlight);
             } else {
@@ -78,13 +87,13 @@ where
         let transform = world_entity.get::<Transform>().cloned();
         let mut game = world_entity.world_mut().resource_mut::<BevyReversi>();
         // TODO replace with GameState check ?
-        if game.0.current_player() == Player::Green || game.0.bot_player().is_none() {
+        if game.0.controller(game.0.current_player()).is_human() {
             if let Some(transform) = transform {
                 let Vec3 { x, z, .. } = transform.translation;
-                let coord = game_coord_to_reversi_coord((x, z));
+                let coord = game_coord_to_reversi_coord((x, z), game.0.board().size());
                 if game.0.valid_moves().contains(&coord) {
                     place_piece(&mut game, coord);
-                    if game.0.bot_player().is_some() {
+                    if !game.0.controller(game.0.current_player()).is_human() {
                         world_entity.world_mut().run_system_once(into_ai_turn_state);
                     }
                 }
@@ -94,9 +103,7 @@ where
 }
 
 fn place_piece(game: &mut Mut<'_, BevyReversi>, coord: (usize, usize)) {
-    game.0.place_piece_and_add_history(coord);
-    game.0.switch_players();
-    game.0.update_valid_moves();
+    game.0.make_move(Move::Place(coord));
 }
 
 pub fn into_ai_turn_state(mut state: ResMut<NextState<GameState>>) {
@@ -109,24 +116,145 @@ pub fn bot_delay_reset(mut timer: ResMut<BevyAiDelay>) {
 }
 
 pub fn bot_make_move(
+    mut commands: Commands,
     mut game: ResMut<BevyReversi>,
     time: Res<Time>,
     mut timer: ResMut<BevyAiDelay>,
     mut state: ResMut<NextState<GameState>>,
+    metrics_query: Query<Entity, With<BevyMetricsDisplay>>,
 ) {
     timer.0.tick(time.delta());
     if timer.0.just_finished() {
-        if Reversi::can_move(game.0.board(), Player::Red) {
-            let bot_move_coord = {
-                let game_copy = game.0.clone();
-                let bot = &mut game.0.bot_player_mut().unwrap().1;
-                bot.get_move(game_copy.clone())
-            };
-            game.0.place_piece_and_add_history(bot_move_coord);
+        let player = game.0.current_player();
+        if Reversi::can_move(game.0.board(), player) {
+            match game.0.controller_move() {
+                Some(Ok(coord)) => game.0.make_move(Move::Place(coord)),
+                Some(Err(err)) => {
+                    error!("{}", err);
+                    state.set(GameState::End);
+                    return;
+                }
+                None => {
+                    state.set(GameState::PlayerTurn);
+                    return;
+                }
+            }
+            if let Some(analysis) = game.0.last_analysis() {
+                display_metrics(&mut commands, analysis, &metrics_query);
+            }
+        } else {
+            game.0.make_move(Move::Pass);
+        }
+        if game.0.controller(game.0.current_player()).is_human() {
+            state.set(GameState::PlayerTurn);
+        } else {
+            // the other side is a bot too, so stay in this state and wait for it
+            timer.0.reset();
+        }
+    }
+}
+
+/// Steps back or forward through the game on the arrow keys or the history buttons, then hands
+/// the turn to whoever is to move, restarting the bot's delay if it is a bot.
+pub fn navigate_history(
+    mut game: ResMut<BevyReversi>,
+    keys: Res<Input<KeyCode>>,
+    mut buttons: Query<
+        (&Interaction, &mut BackgroundColor, &BevyHistoryButton),
+        Changed<Interaction>,
+    >,
+    mut timer: ResMut<BevyAiDelay>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    let mut step = None;
+    if keys.just_pressed(KeyCode::Left) {
+        step = Some(HistoryStep::Back);
+    } else if keys.just_pressed(KeyCode::Right) {
+        step = Some(HistoryStep::Forward);
+    }
+    for (interaction, mut background_color, button) in &mut buttons {
+        match interaction {
+            Interaction::Pressed => step = Some(button.0),
+            Interaction::Hovered => *background_color = BackgroundColor(BUTTON_HOVERED),
+            Interaction::None => *background_color = BackgroundColor(BUTTON_DEFAULT),
+        }
+    }
+
+    let navigated = match step {
+        Some(HistoryStep::Back) => game.0.undo_to_human_turn(),
+        Some(HistoryStep::Forward) => game.0.redo(),
+        None => return,
+    };
+    if navigated.is_ok() {
+        hand_turn_over(&game.0, &mut timer, &mut state);
+    }
+}
+
+/// The file the Bevy game saves its transcript to and loads it from.
+const SAVED_GAME_PATH: &str = "saved_game.txt";
+
+/// Saves the game as a transcript or loads the saved one, on S and L or the buttons. A saved
+/// game on another board size is not loaded, as the board is already set up.
+pub fn save_or_load_game(
+    mut game: ResMut<BevyReversi>,
+    keys: Res<Input<KeyCode>>,
+    mut buttons: Query<
+        (&Interaction, &mut BackgroundColor, &BevyTranscriptButton),
+        Changed<Interaction>,
+    >,
+    mut timer: ResMut<BevyAiDelay>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    let mut action = None;
+    if keys.just_pressed(KeyCode::S) {
+        action = Some(TranscriptAction::Save);
+    } else if keys.just_pressed(KeyCode::L) {
+        action = Some(TranscriptAction::Load);
+    }
+    for (interaction, mut background_color, button) in &mut buttons {
+        match interaction {
+            Interaction::Pressed => action = Some(button.0),
+            Interaction::Hovered => *background_color = BackgroundColor(BUTTON_HOVERED),
+            Interaction::None => *background_color = BackgroundColor(BUTTON_DEFAULT),
+        }
+    }
+
+    match action {
+        Some(TranscriptAction::Save) => {
+            match Transcript::from_game(&game.0).save(SAVED_GAME_PATH) {
+                Ok(()) => info!("Saved the game to {SAVED_GAME_PATH}"),
+                Err(error) => error!("Could not save the game: {error}"),
+            }
         }
-        game.0.switch_players();
-        game.0.update_valid_moves();
+        Some(TranscriptAction::Load) => {
+            let loaded = Transcript::load(SAVED_GAME_PATH).and_then(|transcript| {
+                let size = transcript.size()?;
+                if size != game.0.board().size() {
+                    error!("The saved game is on a {size}x{size} board");
+                    return Ok(false);
+                }
+                transcript.replay(&mut game.0)?;
+                Ok(true)
+            });
+            match loaded {
+                Ok(true) => hand_turn_over(&game.0, &mut timer, &mut state),
+                Ok(false) => {}
+                Err(error) => error!("Could not load the game: {error}"),
+            }
+        }
+        None => {}
+    }
+}
+
+/// Hands the turn to whoever is to move after the game jumped to another position, restarting
+/// the bot's delay if it is a bot.
+fn hand_turn_over(game: &Reversi, timer: &mut BevyAiDelay, state: &mut NextState<GameState>) {
+    if game.controller(game.current_player()).is_human() {
         state.set(GameState::PlayerTurn);
+    } else {
+        // setting the state it is already in does nothing, so restart the delay here
+        state.set(GameState::AiTurn);
+        timer.0.reset();
     }
 }
 
@@ -165,11 +293,12 @@ pub fn update_player_scores(
         if !Reversi::anyone_can_move(game.0.board()) {
             next_state.set(GameState::End);
         } else if *state.get() == GameState::PlayerTurn
-            && !Reversi::can_move(game.0.board(), Player::Green)
+            && !Reversi::can_move(game.0.board(), game.0.current_player())
         {
-            game.0.switch_players();
-            game.0.update_valid_moves();
-            next_state.set(GameState::AiTurn);
+            game.0.make_move(Move::Pass);
+            if !game.0.controller(game.0.current_player()).is_human() {
+                next_state.set(GameState::AiTurn);
+            }
         }
     }
 }
@@ -195,19 +324,14 @@ pub fn maintain_score_display(mut commands: Commands, query: Query<(Entity, &Bev
     }
 }
 
-pub fn display_metrics(
-    mut commands: Commands,
-    game: Res<BevyReversi>,
-    mut query: Query<Entity, With<BevyMetricsDisplay>>,
+fn display_metrics(
+    commands: &mut Commands,
+    analysis: &Analysis,
+    query: &Query<Entity, With<BevyMetricsDisplay>>,
 ) {
-    let bot = &game.0.bot_player().as_ref().unwrap().1;
-    let (expansions, comparisons) = bot.get_metrics();
     let metrics_text_id = commands
         .spawn(TextBundle::from_section(
-            format!(
-                "Expansions: {}\nComparisons: {}\n\n",
-                expansions, comparisons
-            ),
+            format!("{}\n", analysis.to_string().replace(" | ", "\n")),
             TextStyle {
                 font: default(),
                 font_size: 20.0,
@@ -215,21 +339,20 @@ pub fn display_metrics(
             },
         ))
         .id();
-    for entity in &mut query {
+    for entity in query {
         commands.entity(entity).push_children(&[metrics_text_id]);
     }
 }
 
 pub fn show_game_over(world: &mut World) {
-    let winner = world
-        .resource::<BevyReversi>()
-        .0
-        .get_winner()
-        .expect("winning player");
+    let result = match world.resource::<BevyReversi>().0.get_winner() {
+        Some(winner) => format!("Winner: {}", winner),
+        None => String::from("It's a draw"),
+    };
     world.spawn(
         TextBundle {
             text: Text::from_section(
-                format!("GAME OVER\nWinner: {}", winner),
+                format!("GAME OVER\n{}", result),
                 TextStyle {
                     font: default(),
                     font_size: 40.0,
@@ -1,13 +1,13 @@
 use bevy::prelude::*;
 
 use crate::{
-    bot_algorithm::BotAlgorithm,
+    bot::Bot,
     bot_difficulty::BotDifficulty,
+    controller::Controller,
     game::{
         highlight_constants::{BUTTON_DEFAULT, BUTTON_HOVERED},
         states::GameState,
     },
-    player::Player,
     reversi::Reversi,
 };
 
@@ -15,8 +15,8 @@ use super::{
     game_mode::GameMode,
     highlight_constants::{BUTTON_SELECTED, DANGER_DEFAULT, DANGER_HOVERED},
     structs::{
-        BevyBotAlgorithm, BevyBotDifficulty, BevyBotHeuristic, BevyGameMode, BevyMenuContent,
-        BevyPlayButton, BevyReversi,
+        BevyBoardSize, BevyBotAlgorithm, BevyBotDifficulty, BevyBotHeuristic, BevyGameMode,
+        BevyMenuContent, BevyMoveProvider, BevyMoveProviders, BevyPlayButton, BevyReversi,
     },
 };
 
@@ -37,6 +37,40 @@ pub fn handle_gamemode_buttons(
     }
 }
 
+pub fn handle_board_size_buttons(
+    mut config: ResMut<BevyMenuContent>,
+    mut query: Query<(&Interaction, &mut BackgroundColor, &BevyBoardSize), With<Button>>,
+) {
+    for (interaction, mut background_color, board_size) in &mut query {
+        if config.config.board_size.unwrap_or_default() == board_size.0 {
+            *background_color = BackgroundColor(BUTTON_SELECTED);
+        } else {
+            match interaction {
+                Interaction::Pressed => config.config.board_size = Some(board_size.0),
+                Interaction::Hovered => *background_color = BackgroundColor(BUTTON_HOVERED),
+                Interaction::None => *background_color = BackgroundColor(BUTTON_DEFAULT),
+            }
+        }
+    }
+}
+
+pub fn handle_provider_buttons(
+    mut config: ResMut<BevyMenuContent>,
+    mut query: Query<(&Interaction, &mut BackgroundColor, &BevyMoveProvider), With<Button>>,
+) {
+    for (interaction, mut background_color, provider) in &mut query {
+        if config.config.provider == provider.0 {
+            *background_color = BackgroundColor(BUTTON_SELECTED);
+        } else {
+            match interaction {
+                Interaction::Pressed => config.config.provider = provider.0,
+                Interaction::Hovered => *background_color = BackgroundColor(BUTTON_HOVERED),
+                Interaction::None => *background_color = BackgroundColor(BUTTON_DEFAULT),
+            }
+        }
+    }
+}
+
 pub fn handle_difficulty_buttons(
     mut config: ResMut<BevyMenuContent>,
     mut query: Query<(&Interaction, &mut BackgroundColor, &BevyBotDifficulty), With<Button>>,
@@ -75,23 +109,10 @@ pub fn handle_algorithm_buttons(
         if config.config.algorithm.is_some_and(|a| a == algorithm.0) {
             *background_color = BackgroundColor(BUTTON_SELECTED);
         } else {
-            let is_async = algorithm.0 == BotAlgorithm::Async;
             match interaction {
                 Interaction::Pressed => config.config.algorithm = Some(algorithm.0),
-                Interaction::Hovered => {
-                    *background_color = BackgroundColor(if is_async {
-                        DANGER_HOVERED
-                    } else {
-                        BUTTON_HOVERED
-                    })
-                }
-                Interaction::None => {
-                    *background_color = BackgroundColor(if is_async {
-                        DANGER_DEFAULT
-                    } else {
-                        BUTTON_DEFAULT
-                    })
-                }
+                Interaction::Hovered => *background_color = BackgroundColor(BUTTON_HOVERED),
+                Interaction::None => *background_color = BackgroundColor(BUTTON_DEFAULT),
             }
         }
     }
@@ -118,6 +139,7 @@ pub fn handle_play_button(
     mut game: ResMut<BevyReversi>,
     mut state: ResMut<NextState<GameState>>,
     config: Res<BevyMenuContent>,
+    providers: Res<BevyMoveProviders>,
     mut button_query: Query<
         (&Interaction, &mut BackgroundColor),
         (With<Button>, With<BevyPlayButton>),
@@ -125,7 +147,10 @@ pub fn handle_play_button(
     mut text_query: Query<&mut Text, With<BevyPlayButton>>,
 ) {
     for (interaction, mut background_color) in &mut button_query {
-        if config.config.algorithm.is_some() && config.config.difficulty.is_some()
+        if config.config.provider.is_some()
+            || config.config.algorithm.is_some()
+                && config.config.difficulty.is_some()
+                && config.config.heuristic.is_some()
             || config
                 .config
                 .gamemode
@@ -135,7 +160,7 @@ pub fn handle_play_button(
                 text.sections[0].style.color.set_l(0.85);
             }
             match interaction {
-                Interaction::Pressed => start_game(&mut game, &mut state, &config),
+                Interaction::Pressed => start_game(&mut game, &mut state, &config, &providers),
                 Interaction::Hovered => *background_color = BackgroundColor(BUTTON_HOVERED),
                 Interaction::None => *background_color = BackgroundColor(BUTTON_DEFAULT),
             }
@@ -151,23 +176,35 @@ fn start_game(
     game: &mut ResMut<BevyReversi>,
     state: &mut ResMut<NextState<GameState>>,
     config: &Res<BevyMenuContent>,
+    providers: &Res<BevyMoveProviders>,
 ) {
-    if config
-        .config
-        .gamemode
-        .is_some_and(|g| g == GameMode::PlayerVsPlayer)
-    {
-        state.set(GameState::PlayerTurn);
-        game.0 = Reversi::new(None);
-        game.0.update_valid_moves();
+    let bot = || -> Controller {
+        match config.config.provider {
+            Some(index) => Controller::Custom(providers.0[index].clone()),
+            None => Bot::from_difficulty(
+                config.config.difficulty.unwrap(),
+                config.config.algorithm.unwrap(),
+                config.config.heuristic.unwrap(),
+            )
+            .into(),
+        }
+    };
+    let (green, red) = match config.config.gamemode.unwrap_or(GameMode::PlayerVsAi) {
+        GameMode::PlayerVsAi => (Controller::Human, bot()),
+        GameMode::PlayerVsPlayer => (Controller::Human, Controller::Human),
+        GameMode::AiVsAi => (bot(), bot()),
+    };
+    state.set(if green.is_human() {
+        GameState::PlayerTurn
     } else {
-        state.set(GameState::PlayerTurn);
-        game.0 = Reversi::new(Some((
-            Player::Red,
-            config.config.difficulty.unwrap(),
-            config.config.algorithm.unwrap(),
-            config.config.heuristic.unwrap(),
-        )));
-        game.0.update_valid_moves();
-    }
+        GameState::AiTurn
+    });
+    game.0 = Reversi::with_controllers(green, red).with_board_size(
+        config
+            .config
+            .board_size
+            .unwrap_or_default()
+            .squares_per_side(),
+    );
+    game.0.update_valid_moves();
 }
@@ -15,11 +15,12 @@ pub(crate) fn draw_pieces(
     mut queries: Query<(Entity, &Transform, &mut Handle<StandardMaterial>), With<BevyPiece>>,
     game: Res<BevyReversi>,
 ) {
+    let size = game.0.board().size();
     let mut drawn_pieces = HashSet::new();
     for query in queries.iter_mut() {
         let (mut piece, transform, mut material) = (commands.entity(query.0), query.1, query.2);
         let Vec3 { x, z, .. } = transform.translation;
-        let coord = game_coord_to_reversi_coord((x, z));
+        let coord = game_coord_to_reversi_coord((x, z), size);
         let player = game.0.board().get(coord);
         if let Some(player) = player {
             let color = get_color_for_player(player);
@@ -37,7 +38,7 @@ pub(crate) fn draw_pieces(
 
             // better to have this here since only 1 player per round will be placing a new piece (except first round)
             let color = get_color_for_player(player);
-            let (x, z) = reversi_coord_to_game_coord(coord);
+            let (x, z) = reversi_coord_to_game_coord(coord, size);
             commands.spawn((
                 PbrBundle {
                     mesh: meshes.add(shape::UVSphere::default().into()),
@@ -7,8 +7,8 @@ use bevy_mod_picking::{
 use strum::IntoEnumIterator;
 
 use crate::{
-    bot_algorithm::BotAlgorithm, bot_difficulty::BotDifficulty, bot_heuristic::BotHeuristic,
-    player::Player,
+    board_size::BoardSize, bot_algorithm::BotAlgorithm, bot_difficulty::BotDifficulty,
+    bot_heuristic::BotHeuristic, player::Player,
 };
 
 use crate::game::{
@@ -16,18 +16,20 @@ use crate::game::{
     structs::{
         BevyCurrentPlayer, BevyMenuContent, BevyPlayerScore, BevyReversi, BevySquare, PieceCounts,
     },
+    utils::reversi_coord_to_game_coord,
 };
 
 use super::{
     game_mode::GameMode,
     highlight_constants::{BUTTON_DEFAULT, DANGER_DEFAULT, GRID_HIGHLIGHT},
     structs::{
-        BevyBotAlgorithm, BevyBotDifficulty, BevyBotHeuristic, BevyGameConfig, BevyGameMode,
-        BevyMetricsDisplay, BevyPlayButton,
+        BevyBoardSize, BevyBotAlgorithm, BevyBotDifficulty, BevyBotHeuristic, BevyGameConfig,
+        BevyGameMode, BevyHistoryButton, BevyMetricsDisplay, BevyMoveProvider, BevyMoveProviders,
+        BevyPlayButton, BevyTranscriptButton, HistoryStep, TranscriptAction,
     },
 };
 
-pub fn menu_setup(mut commands: Commands) {
+pub fn menu_setup(mut commands: Commands, providers: Res<BevyMoveProviders>) {
     // camera
     let camera_entity = commands
         .spawn(Camera2dBundle {
@@ -140,6 +142,167 @@ pub fn menu_setup(mut commands: Commands) {
                         });
                 });
 
+            // board size
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Flex,
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(8.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle {
+                        text: Text::from_section(
+                            "Board size",
+                            TextStyle {
+                                font: default(),
+                                font_size: 26.0,
+                                color: Color::Hsla {
+                                    hue: 0.0,
+                                    saturation: 0.0,
+                                    lightness: 0.85,
+                                    alpha: 1.0,
+                                },
+                            },
+                        ),
+                        ..Default::default()
+                    });
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                flex_direction: FlexDirection::Row,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        })
+                        .with_children(|parent| {
+                            for board_size in BoardSize::iter() {
+                                parent
+                                    .spawn((
+                                        ButtonBundle {
+                                            background_color: BackgroundColor(BUTTON_DEFAULT),
+                                            style: Style {
+                                                padding: UiRect::all(Val::Px(6.0)),
+                                                margin: UiRect::all(Val::Px(6.0)),
+                                                ..Default::default()
+                                            },
+                                            ..Default::default()
+                                        },
+                                        BevyBoardSize(board_size),
+                                    ))
+                                    .with_children(|btn| {
+                                        btn.spawn(TextBundle {
+                                            text: Text::from_section(
+                                                board_size.to_string(),
+                                                TextStyle {
+                                                    font: default(),
+                                                    font_size: 16.0,
+                                                    color: Color::Hsla {
+                                                        hue: 0.0,
+                                                        saturation: 0.0,
+                                                        lightness: 0.7,
+                                                        alpha: 1.0,
+                                                    },
+                                                },
+                                            ),
+                                            ..Default::default()
+                                        });
+                                    });
+                            }
+                        });
+                });
+
+            // bot, only offered when there are providers besides the built-in one
+            if !providers.0.is_empty() {
+                let names =
+                    [(None, String::from("Built-in"))].into_iter().chain(
+                        providers.0.iter().enumerate().map(|(index, provider)| {
+                            (Some(index), provider.lock().unwrap().name())
+                        }),
+                    );
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            display: Display::Flex,
+                            flex_direction: FlexDirection::Column,
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            row_gap: Val::Px(8.0),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle {
+                            text: Text::from_section(
+                                "Bot",
+                                TextStyle {
+                                    font: default(),
+                                    font_size: 26.0,
+                                    color: Color::Hsla {
+                                        hue: 0.0,
+                                        saturation: 0.0,
+                                        lightness: 0.85,
+                                        alpha: 1.0,
+                                    },
+                                },
+                            ),
+                            ..Default::default()
+                        });
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Row,
+                                    flex_wrap: FlexWrap::Wrap,
+                                    justify_content: JustifyContent::Center,
+                                    max_width: Val::Percent(90.0),
+                                    ..Default::default()
+                                },
+                                ..Default::default()
+                            })
+                            .with_children(|parent| {
+                                for (index, name) in names {
+                                    parent
+                                        .spawn((
+                                            ButtonBundle {
+                                                background_color: BackgroundColor(BUTTON_DEFAULT),
+                                                style: Style {
+                                                    padding: UiRect::all(Val::Px(6.0)),
+                                                    margin: UiRect::all(Val::Px(6.0)),
+                                                    ..Default::default()
+                                                },
+                                                ..Default::default()
+                                            },
+                                            BevyMoveProvider(index),
+                                        ))
+                                        .with_children(|btn| {
+                                            btn.spawn(TextBundle {
+                                                text: Text::from_section(
+                                                    name,
+                                                    TextStyle {
+                                                        font: default(),
+                                                        font_size: 16.0,
+                                                        color: Color::Hsla {
+                                                            hue: 0.0,
+                                                            saturation: 0.0,
+                                                            lightness: 0.7,
+                                                            alpha: 1.0,
+                                                        },
+                                                    },
+                                                ),
+                                                ..Default::default()
+                                            });
+                                        });
+                                }
+                            });
+                    });
+            }
+
             // difficulty
             parent
                 .spawn(NodeBundle {
@@ -179,14 +342,12 @@ pub fn menu_setup(mut commands: Commands) {
                             ..Default::default()
                         })
                         .with_children(|parent| {
-                            let mut it = BotDifficulty::iter();
-                            let mut next = it.next();
-                            while let Some(difficulty) = next {
-                                next = it.next();
+                            for difficulty in BotDifficulty::iter() {
                                 parent
                                     .spawn((
                                         ButtonBundle {
-                                            background_color: if next.is_some() {
+                                            background_color: if difficulty != BotDifficulty::Insane
+                                            {
                                                 BackgroundColor(BUTTON_DEFAULT)
                                             } else {
                                                 BackgroundColor(DANGER_DEFAULT)
@@ -261,18 +422,11 @@ pub fn menu_setup(mut commands: Commands) {
                             ..Default::default()
                         })
                         .with_children(|parent| {
-                            let mut it = BotAlgorithm::iter();
-                            let mut next = it.next();
-                            while let Some(algorithm) = next {
-                                next = it.next();
+                            for algorithm in BotAlgorithm::iter() {
                                 parent
                                     .spawn((
                                         ButtonBundle {
-                                            background_color: if next.is_some() {
-                                                BackgroundColor(BUTTON_DEFAULT)
-                                            } else {
-                                                BackgroundColor(DANGER_DEFAULT)
-                                            },
+                                            background_color: BackgroundColor(BUTTON_DEFAULT),
                                             style: Style {
                                                 padding: UiRect::all(Val::Px(6.0)),
                                                 margin: UiRect::all(Val::Px(6.0)),
@@ -338,6 +492,9 @@ pub fn menu_setup(mut commands: Commands) {
                         .spawn(NodeBundle {
                             style: Style {
                                 flex_direction: FlexDirection::Row,
+                                flex_wrap: FlexWrap::Wrap,
+                                justify_content: JustifyContent::Center,
+                                max_width: Val::Percent(90.0),
                                 ..Default::default()
                             },
                             ..Default::default()
@@ -436,30 +593,33 @@ pub fn board_setup(
     mut materials: ResMut<Assets<StandardMaterial>>,
     game: Res<BevyReversi>,
 ) {
-    // camera
+    let size = game.0.board().size();
+    // camera, far enough away to fit the board
     commands.spawn(Camera3dBundle {
-        transform: Transform::from_xyz(0.0, 15.0, 1.0).looking_at(Vec3::ZERO, Vec3::Y),
+        transform: Transform::from_xyz(0.0, 15.0 * size as f32 / 8.0, 1.0)
+            .looking_at(Vec3::ZERO, Vec3::Y),
         ..default()
     });
     // game board base
     commands.spawn(PbrBundle {
-        mesh: meshes.add(shape::Box::new(10.0, 2.0, 10.0).into()),
+        mesh: meshes.add(shape::Box::new(size as f32 + 2.0, 2.0, size as f32 + 2.0).into()),
         material: materials.add(Color::DARK_GRAY.into()),
         ..default()
     });
     // game board grid
-    for x in -4..4 {
-        for z in -4..4 {
-            let color = if (x % 2_i32).abs() == (z % 2_i32).abs() {
+    for row in 0..size {
+        for col in 0..size {
+            let color = if (row + col) % 2 == 0 {
                 Color::WHITE
             } else {
                 Color::BLACK
             };
+            let (x, z) = reversi_coord_to_game_coord((row, col), size);
             commands.spawn((
                 PbrBundle {
                     mesh: meshes.add(shape::Box::new(1.0, 2.0, 1.0).into()),
                     material: materials.add(color.into()),
-                    transform: Transform::from_xyz(x as f32 + 0.5, 0.2, z as f32 + 0.5),
+                    transform: Transform::from_xyz(x, 0.2, z),
                     ..default()
                 },
                 BevySquare,
@@ -542,6 +702,73 @@ pub fn board_setup(
             ));
             parent_spawn_score_text(parent, Player::Red);
         });
+    // history navigation, also bound to the arrow keys, then saving and loading on S and L
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(16.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                column_gap: Val::Px(12.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|parent| {
+            for (step, label) in [(HistoryStep::Back, "<"), (HistoryStep::Forward, ">")] {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            background_color: BackgroundColor(BUTTON_DEFAULT),
+                            style: Style {
+                                padding: UiRect::horizontal(Val::Px(16.0)),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        BevyHistoryButton(step),
+                    ))
+                    .with_children(|btn| {
+                        btn.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font: default(),
+                                font_size: 32.0,
+                                color: Color::WHITE,
+                            },
+                        ));
+                    });
+            }
+            for (action, label) in [
+                (TranscriptAction::Save, "Save"),
+                (TranscriptAction::Load, "Load"),
+            ] {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            background_color: BackgroundColor(BUTTON_DEFAULT),
+                            style: Style {
+                                padding: UiRect::horizontal(Val::Px(16.0)),
+                                align_items: AlignItems::Center,
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        },
+                        BevyTranscriptButton(action),
+                    ))
+                    .with_children(|btn| {
+                        btn.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font: default(),
+                                font_size: 26.0,
+                                color: Color::WHITE,
+                            },
+                        ));
+                    });
+            }
+        });
     // algorithm metrics
     commands.spawn((
         NodeBundle {
@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 
 use crate::{
-    bot_algorithm::BotAlgorithm, bot_difficulty::BotDifficulty, bot_heuristic::BotHeuristic,
-    player::Player, reversi::Reversi,
+    board_size::BoardSize, bot_algorithm::BotAlgorithm, bot_difficulty::BotDifficulty,
+    bot_heuristic::BotHeuristic, move_provider::SharedMoveProvider, player::Player,
+    reversi::Reversi,
 };
 
 use super::game_mode::GameMode;
@@ -10,9 +11,13 @@ use super::game_mode::GameMode;
 #[derive(Default, Debug)]
 pub struct BevyGameConfig {
     pub gamemode: Option<GameMode>,
+    /// 8x8 unless another size is chosen.
+    pub board_size: Option<BoardSize>,
     pub difficulty: Option<BotDifficulty>,
     pub algorithm: Option<BotAlgorithm>,
     pub heuristic: Option<BotHeuristic>,
+    /// The index of the chosen provider in [`BevyMoveProviders`], if the bot is not built in.
+    pub provider: Option<usize>,
 }
 
 #[derive(Resource)]
@@ -72,6 +77,9 @@ impl Default for BevyAiDelay {
 #[derive(Component, Debug)]
 pub struct BevyGameMode(pub GameMode);
 
+#[derive(Component, Debug)]
+pub struct BevyBoardSize(pub BoardSize);
+
 #[derive(Component, Debug)]
 pub struct BevyBotDifficulty(pub BotDifficulty);
 
@@ -81,12 +89,41 @@ pub struct BevyBotAlgorithm(pub BotAlgorithm);
 #[derive(Component, Debug)]
 pub struct BevyBotHeuristic(pub BotHeuristic);
 
+/// A button choosing the bot, holding its index in [`BevyMoveProviders`] or `None` for the
+/// built-in one.
+#[derive(Component, Debug)]
+pub struct BevyMoveProvider(pub Option<usize>);
+
 #[derive(Component, Debug)]
 pub struct BevyPlayButton;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HistoryStep {
+    Back,
+    Forward,
+}
+
+/// A button stepping through the game's history.
+#[derive(Component, Debug)]
+pub struct BevyHistoryButton(pub HistoryStep);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TranscriptAction {
+    Save,
+    Load,
+}
+
+/// A button saving the game as a transcript or loading one.
+#[derive(Component, Debug)]
+pub struct BevyTranscriptButton(pub TranscriptAction);
+
 #[derive(Component, Default, Debug)]
 pub struct BevyMetricsDisplay;
 
+/// Move providers from outside the crate that can take the bot's place.
+#[derive(Resource, Default, Debug)]
+pub struct BevyMoveProviders(pub Vec<SharedMoveProvider>);
+
 #[derive(Resource, Debug)]
 pub struct BevyReversi(pub Reversi);
 
@@ -1,7 +1,15 @@
-pub(crate) fn reversi_coord_to_game_coord((a, b): (usize, usize)) -> (f32, f32) {
-    (a as f32 - 3.5, b as f32 - 3.5)
+/// How far the board's centre is from its first square, on a board with `size` squares along
+/// each side.
+fn centre_offset(size: usize) -> f32 {
+    (size as f32 - 1.0) / 2.0
 }
 
-pub(crate) fn game_coord_to_reversi_coord((a, b): (f32, f32)) -> (usize, usize) {
-    ((a + 3.5).round() as usize, (b + 3.5).round() as usize)
+pub(crate) fn reversi_coord_to_game_coord((a, b): (usize, usize), size: usize) -> (f32, f32) {
+    let offset = centre_offset(size);
+    (a as f32 - offset, b as f32 - offset)
+}
+
+pub(crate) fn game_coord_to_reversi_coord((a, b): (f32, f32), size: usize) -> (usize, usize) {
+    let offset = centre_offset(size);
+    ((a + offset).round() as usize, (b + offset).round() as usize)
 }
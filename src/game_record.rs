@@ -0,0 +1,184 @@
+use crate::{history::Move, player::Player};
+
+/// Every line a game has taken, as a tree of turns from the starting position. Taking turns
+/// back keeps them for redoing, and playing something else instead starts a variation
+/// alongside them.
+#[derive(Clone, Debug)]
+pub struct GameRecord {
+    /// The starting position first, then every turn played from it in the order first played.
+    nodes: Vec<Node>,
+    current: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    /// The node this turn was played from. The root is its own parent.
+    parent: usize,
+    /// `None` for the root.
+    turn: Option<(Player, Move)>,
+    ply: usize,
+    children: Vec<usize>,
+    /// The child played most recently, which redoing follows.
+    next: Option<usize>,
+}
+
+impl Default for GameRecord {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node {
+                parent: 0,
+                turn: None,
+                ply: 0,
+                children: Vec::new(),
+                next: None,
+            }],
+            current: 0,
+        }
+    }
+}
+
+impl GameRecord {
+    /// Moves forward by `player` playing `m`, following the existing branch if it has been
+    /// played here before.
+    pub(crate) fn push(&mut self, player: Player, m: Move) {
+        let current = self.current;
+        let existing = self.nodes[current]
+            .children
+            .iter()
+            .copied()
+            .find(|&child| self.nodes[child].turn == Some((player, m)));
+        let child = existing.unwrap_or_else(|| {
+            self.nodes.push(Node {
+                parent: current,
+                turn: Some((player, m)),
+                ply: self.nodes[current].ply + 1,
+                children: Vec::new(),
+                next: None,
+            });
+            let child = self.nodes.len() - 1;
+            self.nodes[current].children.push(child);
+            child
+        });
+        self.nodes[current].next = Some(child);
+        self.current = child;
+    }
+
+    /// Moves back one turn, returning the turn taken back.
+    pub(crate) fn back(&mut self) -> Option<(Player, Move)> {
+        let turn = self.nodes[self.current].turn?;
+        self.current = self.nodes[self.current].parent;
+        Some(turn)
+    }
+
+    /// The number of turns played to reach the current position.
+    pub fn ply(&self) -> usize {
+        self.nodes[self.current].ply
+    }
+
+    /// The turn that redoing would play.
+    pub fn redo_turn(&self) -> Option<(Player, Move)> {
+        self.nodes[self.current]
+            .next
+            .and_then(|next| self.nodes[next].turn)
+    }
+
+    /// The turns from the start to the current position, then on along the turns redoing
+    /// would play.
+    pub fn line(&self) -> Vec<(Player, Move)> {
+        let mut line = Vec::new();
+        let mut node = self.current;
+        while let Some(turn) = self.nodes[node].turn {
+            line.push(turn);
+            node = self.nodes[node].parent;
+        }
+        line.reverse();
+
+        let mut node = self.current;
+        while let Some(next) = self.nodes[node].next {
+            line.extend(self.nodes[next].turn);
+            node = next;
+        }
+        line
+    }
+
+    /// Every turn played from the current position so far, the one redoing follows included.
+    pub fn variations(&self) -> Vec<(Player, Move)> {
+        self.nodes[self.current]
+            .children
+            .iter()
+            .filter_map(|&child| self.nodes[child].turn)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot_algorithm::BotAlgorithm,
+        bot_difficulty::BotDifficulty,
+        bot_heuristic::BotHeuristic,
+        reversi::{MoveError, Reversi},
+    };
+
+    #[test]
+    fn test_navigation_keeps_variations() {
+        let mut game = Reversi::new(None);
+        game.update_valid_moves();
+        let start = game.board().clone();
+        let first = game.legal_moves()[0];
+        game.play(first).unwrap();
+        let after_first = game.board().clone();
+        let second = game.legal_moves()[0];
+        let other = game.legal_moves()[1];
+        game.play(second).unwrap();
+        let after_second = game.board().clone();
+
+        // playing something else after undoing starts a variation, which redo then follows
+        game.undo().unwrap();
+        game.play(other).unwrap();
+        game.undo().unwrap();
+        assert_eq!(
+            game.record().variations(),
+            vec![
+                (Player::Red, Move::Place(second)),
+                (Player::Red, Move::Place(other))
+            ]
+        );
+        assert_eq!(
+            game.record().redo_turn(),
+            Some((Player::Red, Move::Place(other)))
+        );
+        game.play(second).unwrap();
+        assert_eq!(game.record().variations().len(), 0);
+        assert_eq!(game.redo(), Err(MoveError::NothingToRedo));
+
+        game.goto(0).unwrap();
+        assert_eq!(game.board(), &start);
+        assert_eq!(game.current_player(), Player::Green);
+        assert_eq!(game.record().line().len(), 2);
+        game.redo().unwrap();
+        assert_eq!(game.board(), &after_first);
+        game.goto(2).unwrap();
+        assert_eq!(game.board(), &after_second);
+        assert_eq!(game.goto(3), Err(MoveError::NoSuchPly(3)));
+    }
+
+    #[test]
+    fn test_undo_skips_bot_turns() {
+        let mut game = Reversi::new(Some((
+            Player::Red,
+            BotDifficulty::Easy,
+            BotAlgorithm::AlphaBeta,
+            BotHeuristic::UniformWeighting,
+        )));
+        game.update_valid_moves();
+        game.play(game.legal_moves()[0]).unwrap();
+        let coord = game.controller_move().unwrap().unwrap();
+        game.play(coord).unwrap();
+
+        game.undo_to_human_turn().unwrap();
+        assert_eq!(game.record().ply(), 0);
+        assert_eq!(game.current_player(), Player::Green);
+    }
+}
@@ -1,8 +1,16 @@
-use crate::player::Player;
+use crate::{board::Bitboard, player::Player};
+
+/// One turn of a game: a piece placed on a square, or a pass by a player without a legal move.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Move {
+    Place((usize, usize)),
+    Pass,
+}
 
 #[derive(Clone, Default, Debug)]
 pub struct History {
-    history: Vec<(Player, (usize, usize), Vec<(usize, usize)>)>,
+    /// Who played each turn, what they played and the pieces it captured.
+    history: Vec<(Player, Move, Bitboard)>,
 }
 
 impl History {
@@ -10,12 +18,37 @@ impl History {
         &mut self,
         player: Player,
         coord: (usize, usize),
-        captured_pieces: Vec<(usize, usize)>,
+        captured_pieces: Bitboard,
     ) {
-        self.history.push((player, coord, captured_pieces.to_vec()));
+        self.history
+            .push((player, Move::Place(coord), captured_pieces));
+    }
+
+    pub(crate) fn push_pass(&mut self, player: Player) {
+        self.history.push((player, Move::Pass, 0));
+    }
+
+    /// The last turn, with the pieces it captured.
+    #[cfg(feature = "terminal")]
+    pub(crate) fn last(&self) -> Option<(Player, Move, Bitboard)> {
+        self.history.last().copied()
     }
 
-    pub(crate) fn pop(&mut self) -> Option<(Player, (usize, usize), Vec<(usize, usize)>)> {
+    pub(crate) fn pop(&mut self) -> Option<(Player, Move, Bitboard)> {
         self.history.pop()
     }
+
+    /// The number of turns played, passes included.
+    pub fn len(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// Every turn in the order it was played, with the player who played it.
+    pub fn moves(&self) -> impl Iterator<Item = (Player, Move)> + '_ {
+        self.history.iter().map(|&(player, m, _)| (player, m))
+    }
 }
@@ -1,13 +1,34 @@
+pub mod benchmark;
 pub mod board;
+pub mod board_size;
 pub mod bot;
 pub mod bot_algorithm;
 pub mod bot_difficulty;
 pub mod bot_heuristic;
+pub mod bot_metrics;
+pub mod cli;
 pub mod constants;
+pub mod controller;
+pub mod endgame_solver;
+pub mod error;
+pub mod game_record;
 pub mod history;
+pub mod mcts;
+pub mod move_provider;
+pub mod pattern;
+pub mod perft;
 pub mod player;
 pub mod reversi;
+pub mod tournament;
+pub mod transcript;
+pub mod transposition_table;
+pub mod tuner;
+pub mod zobrist;
 
+#[cfg(feature = "terminal")]
+pub mod terminal;
+#[cfg(feature = "terminal")]
+pub mod tui;
 #[cfg(feature = "terminal")]
 pub mod utils;
 
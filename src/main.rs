@@ -4,59 +4,18 @@ fn main() {
 
     #[cfg(feature = "terminal")]
     {
-        use kar_reversi_minmax::{
-            bot_algorithm::BotAlgorithm, bot_difficulty::BotDifficulty,
-            bot_heuristic::BotHeuristic, player::Player, reversi::Reversi, utils::clear_terminal,
-        };
-        use strum::IntoEnumIterator;
-        use text_io::try_read;
+        use kar_reversi_minmax::cli;
 
-        let mut difficulty: Option<BotDifficulty> = None;
-        while difficulty.is_none() {
-            println!("Enter a difficulty (caps matter):");
-            for d in BotDifficulty::iter() {
-                println!("{}", d);
-            }
-            println!();
-            difficulty = try_read!().ok();
-            clear_terminal();
+        if let Err(error) = cli::run(std::env::args().skip(1).collect()) {
+            eprintln!("{error}");
+            std::process::exit(1);
         }
-
-        let mut algorithm: Option<BotAlgorithm> = None;
-        while algorithm.is_none() {
-            println!("Enter an algorithm (caps matter):");
-            for d in BotAlgorithm::iter() {
-                println!("{}", d);
-            }
-            println!();
-            algorithm = try_read!().ok();
-            clear_terminal();
-        }
-
-        let mut heuristic: Option<BotHeuristic> = None;
-        while heuristic.is_none() {
-            println!("Enter a heuristic method (caps matter):");
-            for h in BotHeuristic::iter() {
-                println!("{}", h);
-            }
-            println!();
-            heuristic = try_read!().ok();
-            clear_terminal();
-        }
-
-        let mut game = Reversi::new(Some((
-            Player::Red,
-            difficulty.unwrap(),
-            algorithm.unwrap(),
-            heuristic.unwrap(),
-        )));
-        game.start()
     }
 
     #[cfg(feature = "game")]
     {
         use kar_reversi_minmax::game::game;
 
-        game::run_game();
+        game::run_game(Vec::new());
     }
 }
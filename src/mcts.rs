@@ -0,0 +1,243 @@
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use web_time::Instant;
+
+use crate::{
+    board::Board, bot::Bot, bot_heuristic::BotHeuristic, bot_metrics::BotMetrics, player::Player,
+    reversi::Reversi,
+};
+
+/// UCT exploration constant, the textbook value for rewards between 0 and 1.
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+/// How often a heuristic playout plays a random move instead of the best-looking one, so
+/// that playouts from the same position still explore different games.
+const HEURISTIC_PLAYOUT_RANDOMNESS: f64 = 0.1;
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumIter, strum::EnumString, Debug)]
+pub enum Playout {
+    Random,
+    Heuristic,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MctsSettings {
+    /// Iterations per move when the bot has no time limit.
+    pub iterations: usize,
+    pub playout: Playout,
+    pub seed: u64,
+}
+
+impl Default for MctsSettings {
+    fn default() -> Self {
+        Self {
+            iterations: 10_000,
+            playout: Playout::Random,
+            seed: 0,
+        }
+    }
+}
+
+struct Node {
+    /// The move that led here, or `None` for a pass (and the root).
+    coord: Option<(usize, usize)>,
+    /// The player who made that move, whose wins this node counts.
+    player: Player,
+    parent: Option<usize>,
+    depth: usize,
+    children: Vec<usize>,
+    untried_moves: Vec<Option<(usize, usize)>>,
+    visits: u32,
+    wins: f64,
+}
+
+/// Runs UCT from `board` with `player` to move, for `settings.iterations` iterations or until
+/// `deadline`, and returns the most visited move.
+pub(crate) fn best_move(
+    board: &Board,
+    player: Player,
+    settings: MctsSettings,
+    heuristic: BotHeuristic,
+    deadline: Option<Instant>,
+    metrics: &mut BotMetrics,
+) -> Option<(usize, usize)> {
+    let mut rng = SmallRng::seed_from_u64(settings.seed);
+    let mut tree = vec![Node {
+        coord: None,
+        player: player.other(),
+        parent: None,
+        depth: 0,
+        children: Vec::new(),
+        untried_moves: untried_moves(board, player),
+        visits: 0,
+        wins: 0.0,
+    }];
+
+    let mut iterations = 0;
+    while iterations == 0
+        || deadline.map_or(iterations < settings.iterations, |d| Instant::now() < d)
+    {
+        iterations += 1;
+        let mut board = board.clone();
+
+        // selection
+        let mut node = 0;
+        while tree[node].untried_moves.is_empty() && !tree[node].children.is_empty() {
+            node = select_child(&tree, node);
+            if let Some(coord) = tree[node].coord {
+                Reversi::place_piece_on_board(&mut board, coord, tree[node].player);
+            }
+        }
+
+        // expansion
+        if !tree[node].untried_moves.is_empty() {
+            let i = rng.gen_range(0..tree[node].untried_moves.len());
+            let coord = tree[node].untried_moves.swap_remove(i);
+            let mover = tree[node].player.other();
+            if let Some(coord) = coord {
+                Reversi::place_piece_on_board(&mut board, coord, mover);
+            }
+            tree.push(Node {
+                coord,
+                player: mover,
+                parent: Some(node),
+                depth: tree[node].depth + 1,
+                children: Vec::new(),
+                untried_moves: untried_moves(&board, mover.other()),
+                visits: 0,
+                wins: 0.0,
+            });
+            let child = tree.len() - 1;
+            tree[node].children.push(child);
+            node = child;
+            metrics.expansions += 1;
+        }
+
+        // simulation
+        let winner = playout(
+            &mut board,
+            tree[node].player.other(),
+            settings.playout,
+            heuristic,
+            &mut rng,
+            &mut metrics.playout_moves,
+        );
+        metrics.playouts += 1;
+
+        // backpropagation
+        let mut current = Some(node);
+        while let Some(n) = current {
+            tree[n].visits += 1;
+            tree[n].wins += match winner {
+                Some(winner) if winner == tree[n].player => 1.0,
+                Some(_) => 0.0,
+                None => 0.5,
+            };
+            current = tree[n].parent;
+        }
+    }
+
+    metrics.tree_size = tree.len();
+    metrics.depth_reached = tree.iter().map(|n| n.depth).max().unwrap_or_default();
+    tree[0]
+        .children
+        .iter()
+        .max_by_key(|&&child| tree[child].visits)
+        .and_then(|&child| tree[child].coord)
+}
+
+/// The moves available to `player`, a single pass if only the opponent can move, or none
+/// once the game is over.
+fn untried_moves(board: &Board, player: Player) -> Vec<Option<(usize, usize)>> {
+    let moves: Vec<_> = Reversi::get_valid_moves_for_player(board, player)
+        .map(Some)
+        .collect();
+    if moves.is_empty() && Reversi::can_move(board, player.other()) {
+        vec![None]
+    } else {
+        moves
+    }
+}
+
+fn select_child(tree: &[Node], node: usize) -> usize {
+    let log_visits = (tree[node].visits as f64).ln();
+    let uct = |child: usize| {
+        let Node { visits, wins, .. } = tree[child];
+        wins / visits as f64 + EXPLORATION * (log_visits / visits as f64).sqrt()
+    };
+    *tree[node]
+        .children
+        .iter()
+        .max_by(|&&a, &&b| uct(a).total_cmp(&uct(b)))
+        .unwrap()
+}
+
+/// Plays the game out from `board` with `player` to move and returns the winner, counting
+/// the moves played into `moves_played`.
+fn playout(
+    board: &mut Board,
+    mut player: Player,
+    policy: Playout,
+    heuristic: BotHeuristic,
+    rng: &mut SmallRng,
+    moves_played: &mut usize,
+) -> Option<Player> {
+    loop {
+        let moves: Vec<_> = Reversi::get_valid_moves_for_player(board, player).collect();
+        if moves.is_empty() {
+            if !Reversi::can_move(board, player.other()) {
+                break;
+            }
+        } else {
+            let coord = match policy {
+                Playout::Heuristic if !rng.gen_bool(HEURISTIC_PLAYOUT_RANDOMNESS) => *moves
+                    .iter()
+                    .max_by_key(|&&coord| {
+                        let mut child = board.clone();
+                        Reversi::place_piece_on_board(&mut child, coord, player);
+                        Bot::eval(heuristic, &child, player, player.other())
+                    })
+                    .unwrap(),
+                _ => moves[rng.gen_range(0..moves.len())],
+            };
+            Reversi::place_piece_on_board(board, coord, player);
+            *moves_played += 1;
+        }
+        player = player.other();
+    }
+
+    match board
+        .count_for_player(Player::Green)
+        .cmp(&board.count_for_player(Player::Red))
+    {
+        std::cmp::Ordering::Less => Some(Player::Red),
+        std::cmp::Ordering::Equal => None,
+        std::cmp::Ordering::Greater => Some(Player::Green),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot_algorithm::BotAlgorithm;
+
+    #[test]
+    fn test_seeded_search_is_reproducible() {
+        let mut game = Reversi::new(None);
+        game.update_valid_moves();
+        let settings = MctsSettings {
+            iterations: 500,
+            playout: Playout::Heuristic,
+            seed: 7,
+        };
+        let mut bot = Bot::new(BotAlgorithm::Mcts, None, BotHeuristic::TacticalWeighting)
+            .with_mcts_settings(settings);
+
+        let first = bot.get_move(game.clone());
+        let metrics = bot.get_metrics();
+        assert!(game.valid_moves().contains(&first));
+        assert_eq!(metrics.playouts, settings.iterations);
+        assert!(metrics.playout_moves > metrics.playouts);
+        assert!(metrics.tree_size > 1);
+        assert_eq!(bot.get_move(game.clone()), first);
+    }
+}
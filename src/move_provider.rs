@@ -0,0 +1,186 @@
+use std::{
+    error::Error,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use crate::{bot_metrics::BotMetrics, player::Player, reversi::Reversi};
+
+/// Why a [`MoveProvider`] could not choose a move.
+pub type ProviderError = Box<dyn Error + Send + Sync>;
+
+/// A move provider shared between the copies of a game, as front ends clone games freely.
+pub type SharedMoveProvider = Arc<Mutex<Box<dyn MoveProvider>>>;
+
+/// Anything that can choose moves for one side of a game, such as a [`Bot`](crate::bot::Bot).
+///
+/// Implementations can be handed to the front ends boxed, as a
+/// [`Controller`](crate::controller::Controller) or through `run` in the terminal or Bevy
+/// front end, to play strategies from outside the crate.
+pub trait MoveProvider: Send + fmt::Debug {
+    /// The name the front ends list the provider under.
+    fn name(&self) -> String;
+
+    /// Chooses a move for the player to move in `position`, who has at least one legal move.
+    fn choose_move(&mut self, position: PositionView<'_>) -> Result<ChosenMove, ProviderError>;
+}
+
+/// A read-only view of a game for a [`MoveProvider`] to choose its move in.
+#[derive(Clone, Copy, Debug)]
+pub struct PositionView<'a> {
+    game: &'a Reversi,
+}
+
+impl<'a> PositionView<'a> {
+    pub fn new(game: &'a Reversi) -> Self {
+        Self { game }
+    }
+
+    /// The number of squares along each side of the board.
+    pub fn size(&self) -> usize {
+        self.game.board().size()
+    }
+
+    /// The player with a piece on `coord`, if any.
+    pub fn get(&self, coord: (usize, usize)) -> Option<Player> {
+        self.game.board().get(coord)
+    }
+
+    pub fn current_player(&self) -> Player {
+        self.game.current_player()
+    }
+
+    pub fn count(&self, player: Player) -> usize {
+        self.game.board().count_for_player(player)
+    }
+
+    pub fn legal_moves(&self) -> Vec<(usize, usize)> {
+        Reversi::get_valid_moves_for_player(self.game.board(), self.game.current_player()).collect()
+    }
+
+    pub fn is_legal(&self, coord: (usize, usize)) -> bool {
+        self.legal_moves().contains(&coord)
+    }
+
+    pub(crate) fn game(&self) -> &'a Reversi {
+        self.game
+    }
+}
+
+/// A move chosen by a [`MoveProvider`], with what it found on the way if it wants to share it.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ChosenMove {
+    pub coord: (usize, usize),
+    pub analysis: Option<Analysis>,
+}
+
+impl ChosenMove {
+    pub fn new(coord: (usize, usize)) -> Self {
+        Self {
+            coord,
+            analysis: None,
+        }
+    }
+
+    pub fn with_analysis(mut self, analysis: Analysis) -> Self {
+        self.analysis = Some(analysis);
+        self
+    }
+}
+
+/// What a [`MoveProvider`] reports about its choice. Every part is optional.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Analysis {
+    /// The move's score from the mover's point of view, in the provider's own units.
+    pub score: Option<i64>,
+    pub depth: Option<usize>,
+    pub metrics: Option<BotMetrics>,
+    /// Anything else the provider wants shown.
+    pub comment: Option<String>,
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut summary = Vec::new();
+        if let Some(score) = self.score {
+            summary.push(format!("Score: {score}"));
+        }
+        if let Some(depth) = self.depth {
+            summary.push(format!("Depth: {depth}"));
+        }
+        if !summary.is_empty() {
+            writeln!(f, "{}", summary.join(" | "))?;
+        }
+        if let Some(metrics) = self.metrics {
+            writeln!(f, "{metrics}")?;
+        }
+        if let Some(comment) = &self.comment {
+            writeln!(f, "{comment}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{controller::Controller, error::Error};
+
+    /// Always plays the legal move nearest the top left corner.
+    #[derive(Debug)]
+    struct FirstMove;
+
+    impl MoveProvider for FirstMove {
+        fn name(&self) -> String {
+            String::from("First move")
+        }
+
+        fn choose_move(&mut self, position: PositionView<'_>) -> Result<ChosenMove, ProviderError> {
+            let coord = position.legal_moves().into_iter().min().ok_or("no moves")?;
+            Ok(ChosenMove::new(coord).with_analysis(Analysis {
+                comment: Some(String::from("first")),
+                ..Default::default()
+            }))
+        }
+    }
+
+    /// Gives up on every move.
+    #[derive(Debug)]
+    struct Resigns;
+
+    impl MoveProvider for Resigns {
+        fn name(&self) -> String {
+            String::from("Resigns")
+        }
+
+        fn choose_move(&mut self, _: PositionView<'_>) -> Result<ChosenMove, ProviderError> {
+            Err("resigned".into())
+        }
+    }
+
+    #[test]
+    fn test_boxed_providers_play_a_game() {
+        let provider: Box<dyn MoveProvider> = Box::new(FirstMove);
+        let mut game = Reversi::with_controllers(provider.into(), Controller::Human);
+        game.update_valid_moves();
+        assert_eq!(game.controller_move().unwrap().unwrap(), (2, 4));
+        assert_eq!(
+            game.last_analysis().and_then(|a| a.comment.as_deref()),
+            Some("first")
+        );
+
+        let provider: Box<dyn MoveProvider> = Box::new(FirstMove);
+        let mut game = Reversi::with_controllers(provider.into(), Controller::Human);
+        let shared = game.controller(Player::Green).clone();
+        *game.controller_mut(Player::Red) = shared;
+        game.play_to_end().unwrap();
+        assert!(!Reversi::anyone_can_move(game.board()));
+
+        let provider: Box<dyn MoveProvider> = Box::new(Resigns);
+        let mut game = Reversi::with_controllers(provider.into(), Controller::Human);
+        assert!(matches!(
+            game.play_to_end(),
+            Err(Error::Controller(error)) if error.to_string() == "resigned"
+        ));
+    }
+}
@@ -0,0 +1,537 @@
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    path::Path,
+    sync::{OnceLock, RwLock},
+};
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{
+    board::{Bitboard, Board},
+    player::Player,
+};
+
+/// Version of the weight files written by this build. Files of any other version are rejected.
+pub const WEIGHTS_VERSION: u32 = 1;
+
+/// Environment variable naming a weight file to use instead of the built-in weights.
+pub const WEIGHTS_ENV_VAR: &str = "REVERSI_PATTERN_WEIGHTS";
+
+const BINARY_MAGIC: &[u8; 4] = b"RVPW";
+
+/// Maps a square of the board with `size - 1` as its last row and column to its image under
+/// one of the board's rotations or reflections.
+type Symmetry = fn((usize, usize), usize) -> (usize, usize);
+
+/// Number of game stages in the built-in weights.
+const DEFAULT_STAGES: usize = 4;
+
+/// The weights [`BotHeuristic::Pattern`](crate::bot_heuristic::BotHeuristic::Pattern) uses for
+/// each board size.
+static ACTIVE_WEIGHTS: OnceLock<RwLock<HashMap<usize, PatternWeights>>> = OnceLock::new();
+
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum::Display,
+    strum::EnumIter,
+    strum::EnumString,
+    Serialize,
+    Deserialize,
+    Debug,
+)]
+pub enum PatternShape {
+    /// A whole edge plus the two X-squares next to it.
+    Edge,
+    Corner3x3,
+    Corner2x5,
+    /// A whole main diagonal.
+    Diagonal,
+}
+
+impl PatternShape {
+    /// The pattern's squares in the top-left corner. It is matched at every rotation and
+    /// reflection of these that covers a different set of squares.
+    fn squares(self, size: usize) -> Vec<(usize, usize)> {
+        match self {
+            Self::Edge => (0..size)
+                .map(|col| (0, col))
+                .chain([(1, 1), (1, size - 2)])
+                .collect(),
+            Self::Corner3x3 => (0..3)
+                .flat_map(|row| (0..3).map(move |col| (row, col)))
+                .collect(),
+            Self::Corner2x5 => (0..2)
+                .flat_map(|row| (0..5).map(move |col| (row, col)))
+                .collect(),
+            Self::Diagonal => (0..size).map(|i| (i, i)).collect(),
+        }
+    }
+
+    /// Number of weights in the pattern's table: one per arrangement of its squares.
+    pub fn table_len(self, size: usize) -> usize {
+        3usize.pow(self.squares(size).len() as u32)
+    }
+
+    /// The square indices of every placement of the pattern on the board.
+    fn instances(self, size: usize) -> Vec<Vec<usize>> {
+        let n = size - 1;
+        let symmetries: [Symmetry; 8] = [
+            |(row, col), _| (row, col),
+            |(row, col), n| (row, n - col),
+            |(row, col), n| (n - row, col),
+            |(row, col), n| (n - row, n - col),
+            |(row, col), _| (col, row),
+            |(row, col), n| (col, n - row),
+            |(row, col), n| (n - col, row),
+            |(row, col), n| (n - col, n - row),
+        ];
+
+        let mut covered: Vec<Bitboard> = Vec::new();
+        let mut instances = Vec::new();
+        for symmetry in symmetries {
+            let squares: Vec<usize> = self
+                .squares(size)
+                .into_iter()
+                .map(|coord| {
+                    let (row, col) = symmetry(coord, n);
+                    row * size + col
+                })
+                .collect();
+            let bits = squares.iter().fold(0, |bits, &square| bits | 1 << square);
+            if !covered.contains(&bits) {
+                covered.push(bits);
+                instances.push(squares);
+            }
+        }
+        instances
+    }
+}
+
+#[derive(Debug)]
+pub enum PatternWeightsError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// A binary file with the wrong magic number or that ends early.
+    MalformedBinary,
+    UnsupportedVersion(u32),
+    /// The tables don't fit the board size or patterns they claim to be for.
+    InvalidTables(String),
+}
+
+impl fmt::Display for PatternWeightsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read the weight file: {error}"),
+            Self::Json(error) => write!(f, "invalid JSON weight file: {error}"),
+            Self::MalformedBinary => write!(f, "malformed binary weight file"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "weight file version {version} is not supported (expected {WEIGHTS_VERSION})"
+            ),
+            Self::InvalidTables(reason) => write!(f, "invalid weight tables: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for PatternWeightsError {}
+
+impl From<io::Error> for PatternWeightsError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for PatternWeightsError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Json(error)
+    }
+}
+
+/// Weight tables for a set of board patterns, one set of tables per game stage.
+///
+/// A pattern's table is indexed by reading its squares as base-3 digits, the first square
+/// being the least significant: 0 for empty, 1 for the evaluated player's piece and 2 for
+/// the opponent's.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct PatternWeights {
+    pub version: u32,
+    pub size: usize,
+    pub patterns: Vec<PatternShape>,
+    /// `stages[stage][pattern][index]`, with stages evenly dividing the game by piece count.
+    pub stages: Vec<Vec<Vec<i32>>>,
+    #[serde(skip)]
+    instances: Vec<Vec<Vec<usize>>>,
+}
+
+impl PatternWeights {
+    /// All-zero tables for every pattern shape.
+    pub fn zeroed(size: usize, stage_count: usize) -> Self {
+        let patterns: Vec<_> = PatternShape::iter().collect();
+        let tables = patterns
+            .iter()
+            .map(|pattern| vec![0; pattern.table_len(size)])
+            .collect();
+        Self {
+            version: WEIGHTS_VERSION,
+            size,
+            instances: patterns.iter().map(|p| p.instances(size)).collect(),
+            patterns,
+            stages: vec![tables; stage_count],
+        }
+    }
+
+    /// The built-in weights: a positional square table that favours corners and avoids the
+    /// squares next to them, giving way to the plain piece count as the game goes on.
+    pub fn default_for(size: usize) -> Self {
+        let n = size - 1;
+        let square_value = |square: usize, stage: usize| {
+            let (row, col) = (square / size, square % size);
+            let on_edge = |i| i == 0 || i == n;
+            let next_to_edge = |i| i == 1 || i == n - 1;
+            let positional = match (on_edge(row), on_edge(col)) {
+                (true, true) => 30,
+                (true, false) if next_to_edge(col) => -6,
+                (false, true) if next_to_edge(row) => -6,
+                (true, false) | (false, true) => 3,
+                _ if next_to_edge(row) && next_to_edge(col) => -12,
+                _ => 1,
+            };
+            positional * (DEFAULT_STAGES - stage) as i32 + stage as i32
+        };
+
+        let mut weights = Self::zeroed(size, DEFAULT_STAGES);
+        for (stage, tables) in weights.stages.iter_mut().enumerate() {
+            for (table, instances) in tables.iter_mut().zip(&weights.instances) {
+                let squares = &instances[0];
+                for (index, weight) in table.iter_mut().enumerate() {
+                    let mut digits = index;
+                    for &square in squares {
+                        *weight += match digits % 3 {
+                            1 => square_value(square, stage),
+                            2 => -square_value(square, stage),
+                            _ => 0,
+                        };
+                        digits /= 3;
+                    }
+                }
+            }
+        }
+        weights
+    }
+
+    /// Reads a weight file, in the binary format if it starts with the binary magic number
+    /// and as JSON otherwise.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, PatternWeightsError> {
+        let bytes = fs::read(path)?;
+        if bytes.starts_with(BINARY_MAGIC) {
+            Self::from_binary(&bytes)
+        } else {
+            Self::from_json(std::str::from_utf8(&bytes).map_err(|_| {
+                PatternWeightsError::InvalidTables("file is neither binary nor JSON".into())
+            })?)
+        }
+    }
+
+    /// Writes the weights as JSON if `path` ends in `.json` and in the binary format otherwise.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), PatternWeightsError> {
+        let path = path.as_ref();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            fs::write(path, self.to_json()?)?;
+        } else {
+            fs::write(path, self.to_binary())?;
+        }
+        Ok(())
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, PatternWeightsError> {
+        let mut weights: Self = serde_json::from_str(json)?;
+        weights.validate()?;
+        Ok(weights)
+    }
+
+    pub fn to_json(&self) -> Result<String, PatternWeightsError> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Parses the little-endian binary format: the magic number, then the version, board size,
+    /// stage count, pattern count and each pattern's position in [`PatternShape`] as `u32`s,
+    /// then every weight as an `i32`, stage by stage and pattern by pattern.
+    pub fn from_binary(bytes: &[u8]) -> Result<Self, PatternWeightsError> {
+        let mut words = bytes
+            .strip_prefix(BINARY_MAGIC)
+            .ok_or(PatternWeightsError::MalformedBinary)?
+            .chunks(4)
+            .map(|chunk| {
+                chunk
+                    .try_into()
+                    .map_err(|_| PatternWeightsError::MalformedBinary)
+            });
+        let mut next_u32 = || -> Result<u32, PatternWeightsError> {
+            Ok(u32::from_le_bytes(
+                words.next().ok_or(PatternWeightsError::MalformedBinary)??,
+            ))
+        };
+
+        let version = next_u32()?;
+        if version != WEIGHTS_VERSION {
+            return Err(PatternWeightsError::UnsupportedVersion(version));
+        }
+        let size = next_u32()? as usize;
+        let stage_count = next_u32()? as usize;
+        let pattern_count = next_u32()? as usize;
+        let patterns = (0..pattern_count)
+            .map(|_| {
+                let id = next_u32()? as usize;
+                PatternShape::iter().nth(id).ok_or_else(|| {
+                    PatternWeightsError::InvalidTables(format!("unknown pattern {id}"))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !Self::valid_size(size) {
+            return Err(PatternWeightsError::InvalidTables(format!(
+                "unsupported board size {size}"
+            )));
+        }
+        let stages = (0..stage_count)
+            .map(|_| {
+                patterns
+                    .iter()
+                    .map(|pattern| {
+                        (0..pattern.table_len(size))
+                            .map(|_| next_u32().map(|word| word as i32))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        if next_u32().is_ok() {
+            return Err(PatternWeightsError::MalformedBinary);
+        }
+
+        let mut weights = Self {
+            version,
+            size,
+            patterns,
+            stages,
+            instances: Vec::new(),
+        };
+        weights.validate()?;
+        Ok(weights)
+    }
+
+    pub fn to_binary(&self) -> Vec<u8> {
+        let header = [
+            self.version,
+            self.size as u32,
+            self.stages.len() as u32,
+            self.patterns.len() as u32,
+        ];
+        let pattern_ids = self.patterns.iter().map(|&pattern| {
+            PatternShape::iter()
+                .position(|shape| shape == pattern)
+                .unwrap() as u32
+        });
+        let weights = self.stages.iter().flatten().flatten().map(|&w| w as u32);
+
+        let mut bytes = BINARY_MAGIC.to_vec();
+        for word in header.into_iter().chain(pattern_ids).chain(weights) {
+            bytes.extend(word.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Checks that the tables fit the declared board size and patterns, and works out where
+    /// each pattern sits on the board.
+    fn validate(&mut self) -> Result<(), PatternWeightsError> {
+        if self.version != WEIGHTS_VERSION {
+            return Err(PatternWeightsError::UnsupportedVersion(self.version));
+        }
+        if !Self::valid_size(self.size) {
+            return Err(PatternWeightsError::InvalidTables(format!(
+                "unsupported board size {}",
+                self.size
+            )));
+        }
+        if self.stages.is_empty() {
+            return Err(PatternWeightsError::InvalidTables("no stages".into()));
+        }
+        for (stage, tables) in self.stages.iter().enumerate() {
+            if tables.len() != self.patterns.len() {
+                return Err(PatternWeightsError::InvalidTables(format!(
+                    "stage {stage} has {} tables for {} patterns",
+                    tables.len(),
+                    self.patterns.len()
+                )));
+            }
+            for (table, pattern) in tables.iter().zip(&self.patterns) {
+                if table.len() != pattern.table_len(self.size) {
+                    return Err(PatternWeightsError::InvalidTables(format!(
+                        "stage {stage} has {} weights for {pattern}, expected {}",
+                        table.len(),
+                        pattern.table_len(self.size)
+                    )));
+                }
+            }
+        }
+
+        self.instances = self
+            .patterns
+            .iter()
+            .map(|pattern| pattern.instances(self.size))
+            .collect();
+        Ok(())
+    }
+
+    fn valid_size(size: usize) -> bool {
+        (6..=Bitboard::BITS.isqrt() as usize).contains(&size) && size.is_multiple_of(2)
+    }
+
+    /// The stage whose tables score `board`.
+    pub fn stage(&self, board: &Board) -> usize {
+        let squares = self.size * self.size;
+        let pieces = squares - board.empty_bits().count_ones() as usize;
+        (pieces.saturating_sub(4) * self.stages.len() / (squares - 3)).min(self.stages.len() - 1)
+    }
+
+    /// Sums the weights of every pattern placement from `player`'s point of view, minus the
+    /// same from the opponent's, so that the score is symmetric between the players.
+    pub fn evaluate(&self, board: &Board, player: Player) -> i64 {
+        let tables = &self.stages[self.stage(board)];
+        let own = board.bits_for_player(player);
+        let opponent = board.bits_for_player(player.other());
+        let score = |own, opponent| {
+            self.features(own, opponent)
+                .map(|(pattern, index)| tables[pattern][index] as i64)
+                .sum::<i64>()
+        };
+        score(own, opponent) - score(opponent, own)
+    }
+
+    /// The table index of every pattern placement, as `(pattern, index)`.
+    pub(crate) fn features(
+        &self,
+        own: Bitboard,
+        opponent: Bitboard,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.instances
+            .iter()
+            .enumerate()
+            .flat_map(move |(pattern, instances)| {
+                instances.iter().map(move |squares| {
+                    let index = squares.iter().rev().fold(0, |index, &square| {
+                        index * 3
+                            + (own >> square & 1) as usize
+                            + 2 * (opponent >> square & 1) as usize
+                    });
+                    (pattern, index)
+                })
+            })
+    }
+}
+
+/// Makes `weights` the ones used for boards of their size, replacing the built-in or any
+/// previously loaded weights.
+pub fn set_active_weights(weights: PatternWeights) {
+    active_weights()
+        .write()
+        .unwrap()
+        .insert(weights.size, weights);
+}
+
+/// Loads a weight file and makes it the one used for boards of its size.
+pub fn load_active_weights(path: impl AsRef<Path>) -> Result<(), PatternWeightsError> {
+    set_active_weights(PatternWeights::load(path)?);
+    Ok(())
+}
+
+/// Scores `board` for `player` with the active weights for its size, falling back to the
+/// built-in weights.
+pub(crate) fn evaluate(board: &Board, player: Player) -> i64 {
+    if let Some(weights) = active_weights().read().unwrap().get(&board.size()) {
+        return weights.evaluate(board, player);
+    }
+    active_weights()
+        .write()
+        .unwrap()
+        .entry(board.size())
+        .or_insert_with(|| PatternWeights::default_for(board.size()))
+        .evaluate(board, player)
+}
+
+/// The active weights, starting with the file named by [`WEIGHTS_ENV_VAR`] if it is set.
+fn active_weights() -> &'static RwLock<HashMap<usize, PatternWeights>> {
+    ACTIVE_WEIGHTS.get_or_init(|| {
+        let mut weights = HashMap::new();
+        if let Ok(path) = std::env::var(WEIGHTS_ENV_VAR) {
+            match PatternWeights::load(&path) {
+                Ok(loaded) => {
+                    weights.insert(loaded.size, loaded);
+                }
+                Err(error) => eprintln!("Ignoring {path}: {error}"),
+            }
+        }
+        RwLock::new(weights)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weight_files_round_trip() {
+        let mut weights = PatternWeights::default_for(6);
+        weights.stages[1][2][5] = -1234;
+        let dir = std::env::temp_dir();
+
+        for name in ["pattern_weights_test.json", "pattern_weights_test.bin"] {
+            let path = dir.join(name);
+            weights.save(&path).unwrap();
+            assert_eq!(PatternWeights::load(&path).unwrap(), weights);
+            fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_invalid_weight_files_are_rejected() {
+        let weights = PatternWeights::default_for(6);
+
+        let mut bytes = weights.to_binary();
+        bytes[4] = 2;
+        assert!(matches!(
+            PatternWeights::from_binary(&bytes),
+            Err(PatternWeightsError::UnsupportedVersion(2))
+        ));
+
+        let bytes = weights.to_binary();
+        assert!(matches!(
+            PatternWeights::from_binary(&bytes[..bytes.len() - 4]),
+            Err(PatternWeightsError::MalformedBinary)
+        ));
+
+        let mut truncated = weights.clone();
+        truncated.stages[0][0].pop();
+        assert!(matches!(
+            PatternWeights::from_json(&truncated.to_json().unwrap()),
+            Err(PatternWeightsError::InvalidTables(_))
+        ));
+    }
+
+    #[test]
+    fn test_patterns_cover_the_board_symmetrically() {
+        let weights = PatternWeights::default_for(8);
+        let counts: Vec<_> = weights.instances.iter().map(Vec::len).collect();
+        assert_eq!(counts, [4, 4, 8, 2]);
+
+        let board = Board::new(8);
+        assert_eq!(weights.evaluate(&board, Player::Green), 0);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{history::Move, reversi::Reversi};
+
+/// The number of lines of play `depth` turns long from `game`'s position, counting a pass
+/// as a turn and a game that ends sooner as one line. Walks the tree with the moves and
+/// passes the bots search with, so wrong counts point at move generation.
+pub fn perft(game: &Reversi, depth: usize) -> u64 {
+    count(&mut game.clone(), depth)
+}
+
+/// [`perft`] split by the current player's first turn, in the order moves are generated.
+pub fn divide(game: &Reversi, depth: usize) -> Vec<(Move, u64)> {
+    let mut game = game.clone();
+    if depth == 0 {
+        return Vec::new();
+    }
+    let moves: Vec<_> =
+        Reversi::get_valid_moves_for_player(game.board(), game.current_player()).collect();
+    if moves.is_empty() {
+        if !Reversi::can_move(game.board(), game.current_player().other()) {
+            return Vec::new();
+        }
+        game.switch_players();
+        return vec![(Move::Pass, count(&mut game, depth - 1))];
+    }
+    moves
+        .into_iter()
+        .map(|coord| {
+            game.place_piece_and_add_history(coord);
+            game.switch_players();
+            let nodes = count(&mut game, depth - 1);
+            game.undo_turn();
+            (Move::Place(coord), nodes)
+        })
+        .collect()
+}
+
+fn count(game: &mut Reversi, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let moves: Vec<_> =
+        Reversi::get_valid_moves_for_player(game.board(), game.current_player()).collect();
+    if moves.is_empty() {
+        if !Reversi::can_move(game.board(), game.current_player().other()) {
+            return 1;
+        }
+        game.switch_players();
+        let nodes = count(game, depth - 1);
+        game.switch_players();
+        return nodes;
+    }
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+    moves
+        .into_iter()
+        .map(|coord| {
+            game.place_piece_and_add_history(coord);
+            game.switch_players();
+            let nodes = count(game, depth - 1);
+            game.undo_turn();
+            nodes
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_perft_from_the_start() {
+        let game = Reversi::new(None);
+        let expected = [1, 4, 12, 56, 244, 1_396, 8_200, 55_092, 390_216, 3_005_288];
+        for (depth, &nodes) in expected.iter().enumerate() {
+            assert_eq!(perft(&game, depth), nodes, "depth {depth}");
+        }
+    }
+
+    #[test]
+    fn test_perft_counts_passes_and_game_ends() {
+        // Red has to pass, then Green's only move ends the game
+        let game = Reversi::from_position_str(&format!("{}-OXXXXXX O", "-".repeat(56))).unwrap();
+        assert_eq!(divide(&game, 1), vec![(Move::Pass, 1)]);
+        assert_eq!(divide(&game, 3), vec![(Move::Pass, 1)]);
+        assert_eq!(perft(&game, 2), 1);
+        assert_eq!(perft(&game, 5), 1);
+    }
+}
@@ -11,4 +11,23 @@ impl Player {
             Player::Red => Player::Green,
         }
     }
+
+    /// The player's letter in position strings: `X` for Green, who moves first like Black,
+    /// and `O` for Red.
+    pub fn symbol(&self) -> char {
+        match self {
+            Player::Green => 'X',
+            Player::Red => 'O',
+        }
+    }
+
+    /// The player written as `symbol` in a position string, in either case. `*` and `B` are
+    /// also read as Green and `W` as Red, as some tools write them.
+    pub fn from_symbol(symbol: char) -> Option<Self> {
+        match symbol.to_ascii_uppercase() {
+            'X' | '*' | 'B' => Some(Player::Green),
+            'O' | 'W' => Some(Player::Red),
+            _ => None,
+        }
+    }
 }
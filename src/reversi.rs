@@ -1,78 +1,222 @@
-use std::{cmp::Ordering, collections::HashSet};
+use std::{cmp::Ordering, fmt};
 
 use crate::{
-    board::Board, bot::Bot, bot_algorithm::BotAlgorithm, bot_difficulty::BotDifficulty,
-    bot_heuristic::BotHeuristic, constants::DIRECTIONS, history::History, player::Player,
+    board::{Bitboard, Board},
+    bot::Bot,
+    bot_algorithm::BotAlgorithm,
+    bot_difficulty::BotDifficulty,
+    bot_heuristic::BotHeuristic,
+    controller::Controller,
+    error::Error,
+    game_record::GameRecord,
+    history::{History, Move},
+    move_provider::{Analysis, PositionView, ProviderError},
+    player::Player,
+    zobrist,
 };
 
 #[cfg(feature = "terminal")]
-use crate::utils;
+use crate::{
+    transcript::{self, Transcript},
+    utils,
+};
 #[cfg(feature = "terminal")]
 use if_chain::if_chain;
 #[cfg(feature = "terminal")]
-use text_io::try_read;
+use text_io::read;
+
+#[cfg(feature = "terminal")]
+const TERMINAL_HELP: &str =
+    "Moves are squares such as d3 or F5: a column letter, then a row number.
+Commands:
+  moves           list the legal moves
+  hint            suggest a move
+  eval            show how the position stands
+  pass            pass when there is no legal move
+  undo            take back your last move
+  redo            play a taken back move again
+  goto N          go to the position after turn N
+  save FILE       save the game as a transcript
+  load FILE       load a saved game
+  setup POSITION  start over from a position string
+  quit            leave the game
+  help            show this list";
+
+/// What a human typed on their turn in the terminal.
+#[cfg(feature = "terminal")]
+enum TerminalInput {
+    Move((usize, usize)),
+    Pass,
+    Quit,
+    Undo,
+    Redo,
+    Goto(usize),
+    Save(String),
+    Load(String),
+    Setup(String),
+}
+
+/// Why a move, pass or undo was refused.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MoveError {
+    OffBoard((usize, usize)),
+    Occupied((usize, usize)),
+    /// The square is empty but a piece there would not capture anything.
+    NoCaptures((usize, usize)),
+    /// Passing is only allowed without a legal move.
+    CannotPass,
+    GameOver,
+    NothingToUndo,
+    NothingToRedo,
+    /// The current line of the game is not that many turns long.
+    NoSuchPly(usize),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OffBoard(coord) => write!(f, "{coord:?} is off the board"),
+            Self::Occupied(coord) => write!(f, "{coord:?} is already taken"),
+            Self::NoCaptures(coord) => write!(f, "a piece on {coord:?} captures nothing"),
+            Self::CannotPass => write!(f, "cannot pass with a legal move to play"),
+            Self::GameOver => write!(f, "the game is over"),
+            Self::NothingToUndo => write!(f, "there is no move to undo"),
+            Self::NothingToRedo => write!(f, "there is no move to redo"),
+            Self::NoSuchPly(ply) => write!(f, "the game has no turn {ply}"),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
 
 #[derive(Clone, Debug)]
 pub struct Reversi {
     board: Board,
-    bot_player: Option<(Player, Bot)>,
+    /// Green's controller, then Red's.
+    controllers: [Controller; 2],
     current_player: Player,
+    /// The turns leading to the current position, for taking them back.
     history: History,
+    record: GameRecord,
     valid_moves: Vec<(usize, usize)>,
+    last_analysis: Option<Analysis>,
 }
 
 impl Default for Reversi {
     fn default() -> Self {
         Self {
             board: Board::new(8),
-            bot_player: Default::default(),
+            controllers: Default::default(),
             current_player: Player::Red,
             history: Default::default(),
+            record: Default::default(),
             valid_moves: Default::default(),
+            last_analysis: None,
         }
     }
 }
 
 impl Reversi {
+    /// A game between humans, except for a bot playing `bot_player`'s colour if there is one.
     pub fn new(bot_player: Option<(Player, BotDifficulty, BotAlgorithm, BotHeuristic)>) -> Self {
+        let mut game = Self::with_controllers(Controller::Human, Controller::Human);
+        if let Some((player, difficulty, algorithm, heuristic)) = bot_player {
+            *game.controller_mut(player) =
+                Bot::from_difficulty(difficulty, algorithm, heuristic).into();
+        }
+        game
+    }
+
+    pub fn with_controllers(green: Controller, red: Controller) -> Self {
         Self {
-            board: Board::new(8),
-            bot_player: bot_player.map_or(None, |(p, difficulty, algorithm, heuristic)| {
-                type D = BotDifficulty;
-                Some((
-                    p,
-                    Bot::new(
-                        algorithm,
-                        match difficulty {
-                            D::Easy => Some(1),
-                            D::Medium => Some(4),
-                            D::Hard => Some(8),
-                            D::Insane => Some(12),
-                        },
-                        heuristic,
-                    ),
-                ))
-            }),
+            controllers: [green, red],
             current_player: Player::Green,
             ..Default::default()
         }
     }
 
+    /// A game between humans from `board`, with `to_move` to play first.
+    pub fn from_position(board: Board, to_move: Player) -> Self {
+        Self::with_controllers(Controller::Human, Controller::Human).with_position(board, to_move)
+    }
+
+    /// A game between humans from a position string, such as
+    /// `---------------------------OX------XO--------------------------- X` for the start: the
+    /// squares as read by [`Board::from_squares_str`], then the player to move as `X` or `O`.
+    pub fn from_position_str(position: &str) -> Result<Self, Error> {
+        let (board, to_move) = Self::parse_position(position)?;
+        Ok(Self::from_position(board, to_move))
+    }
+
+    /// Starts the game over from `board`, with `to_move` to play first.
+    pub fn with_position(mut self, board: Board, to_move: Player) -> Self {
+        self.board = board;
+        self.history = Default::default();
+        self.record = Default::default();
+        self.current_player = to_move;
+        self.last_analysis = None;
+        self.update_valid_moves();
+        self
+    }
+
+    /// The current position in the format read by [`Reversi::from_position_str`].
+    pub fn to_position_string(&self) -> String {
+        format!(
+            "{} {}",
+            self.board.to_squares_string(),
+            self.current_player.symbol()
+        )
+    }
+
+    fn parse_position(position: &str) -> Result<(Board, Player), Error> {
+        let position = position.trim_end();
+        let mut chars = position.chars();
+        let to_move = chars
+            .next_back()
+            .ok_or_else(|| Error::InvalidPosition(String::from("the position is empty")))?;
+        let to_move = Player::from_symbol(to_move).ok_or_else(|| {
+            Error::InvalidPosition(format!("unexpected player to move '{to_move}'"))
+        })?;
+        Ok((Board::from_squares_str(chars.as_str())?, to_move))
+    }
+
+    /// Starts the game over on an empty board with `size` squares along each side.
+    ///
+    /// Panics if `size` is odd, below 6 or above 10; see [`Reversi::try_with_board_size`].
+    pub fn with_board_size(self, size: usize) -> Self {
+        self.try_with_board_size(size)
+            .unwrap_or_else(|error| panic!("{error}"))
+    }
+
+    /// Starts the game over on an empty board with `size` squares along each side, which must
+    /// be even and from 6 to 10.
+    pub fn try_with_board_size(mut self, size: usize) -> Result<Self, Error> {
+        self.board = Board::try_new(size)?;
+        self.history = Default::default();
+        self.record = Default::default();
+        self.current_player = Player::Green;
+        self.last_analysis = None;
+        self.valid_moves.clear();
+        Ok(self)
+    }
+
+    /// Prints the board, after the analysis of the last move if a controller chose it.
     #[cfg(feature = "terminal")]
     pub fn show_board(&self, with_metrics: bool) {
         utils::clear_terminal();
         if_chain!(if with_metrics;
-            if let Some((_, bot)) = self.bot_player.as_ref();
+            if !self.controller(self.current_player.other()).is_human();
+            if let Some(analysis) = &self.last_analysis;
             then {
-                bot.show_metrics();
+                print!("This turn's analysis:\n{}", analysis);
             }
         );
         println!(
             "{}: {} | {}: {}",
             Player::Green,
-            self.board.pieces_for_player(Player::Green).count(),
+            self.board.count_for_player(Player::Green),
             Player::Red,
-            self.board.pieces_for_player(Player::Red).count()
+            self.board.count_for_player(Player::Red)
         );
         println!("{}", self.board);
     }
@@ -83,27 +227,55 @@ impl Reversi {
         use std::thread;
 
         while Self::anyone_can_move(&self.board) {
-            let current_player_is_bot = self
-                .bot_player
-                .as_ref()
-                .is_some_and(|(p, _)| *p == self.current_player);
-            self.show_board(!current_player_is_bot);
-
             self.update_valid_moves();
-            if current_player_is_bot {
-                let sleep_time = time::Duration::from_millis(1500);
-                thread::sleep(sleep_time);
-                let game = self.clone();
-                let (_, bot) = self.bot_player.as_mut().unwrap();
-                let coord = bot.get_move(game);
-                self.place_piece_and_add_history(coord);
-            } else if Self::can_move(&self.board, self.current_player) {
-                let coord =
-                    self.get_valid_coordinate_input(Some(|| println!("ERROR: Invalid input")));
-                self.place_piece_and_add_history(coord);
-            }
+            self.show_board(true);
 
-            self.switch_players();
+            let is_human = self.controller(self.current_player).is_human();
+            if self.valid_moves.is_empty() && !is_human {
+                self.make_move(Move::Pass);
+                continue;
+            }
+            if !is_human {
+                thread::sleep(time::Duration::from_millis(1500));
+            }
+            let outcome: Result<(), Box<dyn std::error::Error>> = match self.controller_move() {
+                None => match self.read_input() {
+                    TerminalInput::Move(coord) => {
+                        self.make_move(Move::Place(coord));
+                        Ok(())
+                    }
+                    TerminalInput::Pass => {
+                        self.make_move(Move::Pass);
+                        Ok(())
+                    }
+                    TerminalInput::Quit => return,
+                    TerminalInput::Undo => self.undo_to_human_turn().map_err(Into::into),
+                    TerminalInput::Redo => self.redo().map_err(Into::into),
+                    TerminalInput::Goto(ply) => self.goto(ply).map_err(Into::into),
+                    TerminalInput::Save(path) => {
+                        Transcript::from_game(self).save(path).map_err(Into::into)
+                    }
+                    TerminalInput::Load(path) => Transcript::load(path)
+                        .and_then(|transcript| transcript.replay(self))
+                        .map_err(Into::into),
+                    TerminalInput::Setup(position) => Self::parse_position(&position)
+                        .map(|(board, to_move)| {
+                            *self = std::mem::take(self).with_position(board, to_move);
+                        })
+                        .map_err(Into::into),
+                },
+                Some(Ok(coord)) => {
+                    self.make_move(Move::Place(coord));
+                    Ok(())
+                }
+                Some(Err(error)) => {
+                    println!("ERROR: {}", error);
+                    return;
+                }
+            };
+            if let Err(error) = outcome {
+                println!("ERROR: {}", error);
+            }
         }
 
         self.show_board(true);
@@ -119,34 +291,7 @@ impl Reversi {
         board: &Board,
         player: Player,
     ) -> impl Iterator<Item = (usize, usize)> + '_ {
-        let n = board.size();
-        let in_bounds = |(a, b): (usize, usize)| a < n && b < n;
-        board
-            .pieces_for_player(player)
-            .fold(HashSet::new(), |mut s, start| {
-                for (i, j) in DIRECTIONS {
-                    let increment = |(a, b): (usize, usize)| (a.wrapping_add(i), b.wrapping_add(j));
-
-                    let mut coord = increment(start);
-                    if !in_bounds(coord) || board.get(coord).map_or(true, |p| p == player) {
-                        continue;
-                    }
-
-                    while in_bounds(coord) {
-                        let p = board.get(coord);
-                        match p {
-                            Some(p) if p == player => break,
-                            Some(_) => coord = increment(coord),
-                            None => {
-                                s.insert(coord);
-                                break;
-                            }
-                        }
-                    }
-                }
-                s
-            })
-            .into_iter()
+        board.coords(board.valid_moves_bits(player))
     }
 
     pub(crate) fn anyone_can_move(board: &Board) -> bool {
@@ -154,13 +299,14 @@ impl Reversi {
     }
 
     pub(crate) fn can_move(board: &Board, player: Player) -> bool {
-        Self::get_valid_moves_for_player(board, player).count() > 0
+        board.valid_moves_bits(player) != 0
     }
 
+    /// The player with more pieces, or `None` when they have as many.
     pub fn get_winner(&self) -> Option<Player> {
         let (black_pieces, red_pieces) = (
-            self.board.pieces_for_player(Player::Green).count(),
-            self.board.pieces_for_player(Player::Red).count(),
+            self.board.count_for_player(Player::Green),
+            self.board.count_for_player(Player::Red),
         );
         match black_pieces.cmp(&red_pieces) {
             Ordering::Less => Some(Player::Red),
@@ -177,32 +323,151 @@ impl Reversi {
         }
     }
 
+    /// Reads a move or a command from a human, until one makes sense. Commands that only show
+    /// something are answered here.
     #[cfg(feature = "terminal")]
-    fn get_valid_coordinate_input(&self, error_msg: Option<impl Fn()>) -> (usize, usize) {
+    fn read_input(&self) -> TerminalInput {
+        let size = self.board.size();
+        if self.valid_moves.is_empty() {
+            println!(
+                "{} has no legal move, so must pass (type pass, or help for the other commands):",
+                self.current_player
+            );
+        } else {
+            println!(
+                "{}, enter a move such as {} or a command (type help for a list):",
+                self.current_player,
+                transcript::square_name(self.valid_moves[0], size)
+            );
+        }
         loop {
-            let row: Result<usize, _> = try_read!();
-            let col: Result<usize, _> = try_read!();
-
-            // TODO clean
-            if row
-                .as_ref()
-                .is_ok_and(|&r| r.wrapping_sub(1) < self.board.size())
-                && col
-                    .as_ref()
-                    .is_ok_and(|&c| c.wrapping_sub(1) < self.board.size())
-                && self.valid_moves.contains(&(
-                    row.as_ref().unwrap().wrapping_sub(1),
-                    col.as_ref().unwrap().wrapping_sub(1),
-                ))
-            {
-                return (row.unwrap().wrapping_sub(1), col.unwrap().wrapping_sub(1));
-            } else if let Some(ref _error_msg) = error_msg {
-                #[cfg(feature = "terminal")]
-                _error_msg()
+            let line: String = read!("{}\n");
+            let words: Vec<_> = line.split_whitespace().collect();
+            let input = match words[..] {
+                [] => continue,
+                ["help"] => {
+                    println!("{TERMINAL_HELP}");
+                    continue;
+                }
+                ["moves"] => {
+                    let moves: Vec<_> = self
+                        .valid_moves
+                        .iter()
+                        .map(|&coord| transcript::square_name(coord, size))
+                        .collect();
+                    println!("Legal moves: {}", moves.join(" "));
+                    continue;
+                }
+                ["hint"] => {
+                    match self.hint() {
+                        Ok(coord) => println!("Hint: {}", transcript::square_name(coord, size)),
+                        Err(error) => println!("ERROR: {}", error),
+                    }
+                    continue;
+                }
+                ["eval"] => {
+                    self.show_evaluation();
+                    continue;
+                }
+                ["pass"] => match self.can_pass() {
+                    Ok(()) => Ok(TerminalInput::Pass),
+                    Err(error) => Err(error.to_string()),
+                },
+                ["undo"] => Ok(TerminalInput::Undo),
+                ["redo"] => Ok(TerminalInput::Redo),
+                ["quit"] => Ok(TerminalInput::Quit),
+                ["goto", ply] => ply
+                    .parse()
+                    .map(TerminalInput::Goto)
+                    .map_err(|_| format!("'{ply}' is not a turn number")),
+                ["save", path] => Ok(TerminalInput::Save(String::from(path))),
+                ["load", path] => Ok(TerminalInput::Load(String::from(path))),
+                ["setup", _, ..] => Ok(TerminalInput::Setup(words[1..].join(" "))),
+                ["goto" | "save" | "load" | "setup"] => Err(format!(
+                    "{} needs an argument (type help for a list)",
+                    words[0]
+                )),
+                [word] => match transcript::parse_square(word, size) {
+                    Some(coord) => match self.check_move(coord) {
+                        Ok(()) => Ok(TerminalInput::Move(coord)),
+                        Err(error) => Err(self.describe_move_error(error)),
+                    },
+                    None if Self::looks_like_square(word) => {
+                        Err(format!("{word} is off the board"))
+                    }
+                    None => Err(format!(
+                        "'{word}' is not a square or a command (type help for a list)"
+                    )),
+                },
+                _ => Err(format!(
+                    "'{}' is not a square or a command (type help for a list)",
+                    line.trim()
+                )),
+            };
+            match input {
+                Ok(input) => return input,
+                Err(error) => println!("ERROR: {}", error),
             }
         }
     }
 
+    /// Whether `word` is a letter and a number, like a square whether on the board or not.
+    #[cfg(feature = "terminal")]
+    fn looks_like_square(word: &str) -> bool {
+        let mut chars = word.chars();
+        chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+            && !chars.as_str().is_empty()
+            && chars.all(|c| c.is_ascii_digit())
+    }
+
+    /// `error` with any square named as the terminal game reads them.
+    #[cfg(feature = "terminal")]
+    pub(crate) fn describe_move_error(&self, error: MoveError) -> String {
+        let name = |coord| transcript::square_name(coord, self.board.size());
+        match error {
+            MoveError::Occupied(coord) => format!("{} is already taken", name(coord)),
+            MoveError::NoCaptures(coord) => format!("{} would flip no pieces", name(coord)),
+            error => error.to_string(),
+        }
+    }
+
+    /// Prints the piece and move counts, and the position's score for the player to move by
+    /// a couple of heuristics.
+    #[cfg(feature = "terminal")]
+    fn show_evaluation(&self) {
+        let player = self.current_player;
+        let moves = |player| Self::get_valid_moves_for_player(&self.board, player).count();
+        println!(
+            "Pieces: {} {} to {} | Moves: {} {} to {}",
+            player,
+            self.board.count_for_player(player),
+            self.board.count_for_player(player.other()),
+            player,
+            moves(player),
+            moves(player.other())
+        );
+        let scores: Vec<_> = [BotHeuristic::TacticalWeighting, BotHeuristic::Pattern]
+            .into_iter()
+            .map(|heuristic| {
+                let score = Bot::eval(heuristic, &self.board, player, player);
+                format!("{heuristic}: {score:+}")
+            })
+            .collect();
+        println!("Score for {}: {}", player, scores.join(" | "));
+    }
+
+    /// Plays `m` for the current player without checking it, records it and hands the turn
+    /// over.
+    pub(crate) fn make_move(&mut self, m: Move) {
+        match m {
+            Move::Place(coord) => self.place_piece_and_add_history(coord),
+            Move::Pass => self.history.push_pass(self.current_player),
+        }
+        self.record.push(self.current_player, m);
+        self.switch_players();
+        self.update_valid_moves();
+    }
+
     pub(crate) fn place_piece_and_add_history(&mut self, coord: (usize, usize)) {
         let captured_pieces =
             Self::place_piece_on_board(&mut self.board, coord, self.current_player);
@@ -214,13 +479,9 @@ impl Reversi {
         board: &mut Board,
         coord: (usize, usize),
         player: Player,
-    ) -> Vec<(usize, usize)> {
-        board.set(coord, Some(player));
-
-        let captured_pieces = Self::get_captures_for_position(board, coord).to_vec();
-        for &c in captured_pieces.iter() {
-            board.switch_piece(c);
-        }
+    ) -> Bitboard {
+        let captured_pieces = Self::get_captures_for_position(board, coord, player);
+        board.apply_move(coord, player, captured_pieces);
 
         captured_pieces
     }
@@ -229,67 +490,248 @@ impl Reversi {
         self.current_player = self.current_player.other();
     }
 
-    pub fn bot_player(&self) -> Option<&(Player, Bot)> {
-        self.bot_player.as_ref()
+    pub fn controller(&self, player: Player) -> &Controller {
+        &self.controllers[player as usize]
+    }
+
+    pub fn controller_mut(&mut self, player: Player) -> &mut Controller {
+        &mut self.controllers[player as usize]
+    }
+
+    /// The move chosen by the current player's controller, or `None` if a human has to choose
+    /// it. The current player must have a legal move. Moves outside the rules are errors.
+    pub fn controller_move(&mut self) -> Option<Result<(usize, usize), ProviderError>> {
+        let position = self.position();
+        let player = position.current_player;
+        let chosen = match self
+            .controller_mut(player)
+            .choose_move(PositionView::new(&position))?
+        {
+            Ok(chosen) => chosen,
+            Err(error) => return Some(Err(error)),
+        };
+        if !Self::get_valid_moves_for_player(&self.board, player).any(|m| m == chosen.coord) {
+            return Some(Err(format!(
+                "{} chose the illegal move {:?}",
+                player, chosen.coord
+            )
+            .into()));
+        }
+        self.last_analysis = chosen.analysis;
+        Some(Ok(chosen.coord))
+    }
+
+    /// A move for the current player, chosen by a copy of the game's bot: the current player's
+    /// own if they are one, else their opponent's, else a moderate one if humans play both
+    /// sides. Fails if the current player has no legal move.
+    pub fn hint(&self) -> Result<(usize, usize), Error> {
+        let player = self.current_player;
+        let mut bot = self
+            .controller(player)
+            .as_bot()
+            .or_else(|| self.controller(player.other()).as_bot())
+            .cloned()
+            .unwrap_or_else(|| {
+                Bot::from_difficulty(
+                    BotDifficulty::Medium,
+                    BotAlgorithm::AlphaBeta,
+                    BotHeuristic::TacticalWeighting,
+                )
+            });
+        bot.try_get_move(self.clone())
+    }
+
+    /// What the controller that chose the last move reported about it.
+    pub fn last_analysis(&self) -> Option<&Analysis> {
+        self.last_analysis.as_ref()
     }
 
-    pub fn bot_player_mut(&mut self) -> Option<&mut (Player, Bot)> {
-        self.bot_player.as_mut()
+    /// Lets the controllers play the game out and returns the winner. Fails if it becomes a
+    /// human's turn or a controller fails.
+    pub fn play_to_end(&mut self) -> Result<Option<Player>, Error> {
+        self.update_valid_moves();
+        while Self::anyone_can_move(&self.board) {
+            if !self.valid_moves.is_empty() {
+                let coord = self
+                    .controller_move()
+                    .ok_or(Error::HumanToMove(self.current_player))??;
+                self.make_move(Move::Place(coord));
+            } else {
+                self.make_move(Move::Pass);
+            }
+        }
+        Ok(self.get_winner())
+    }
+
+    /// A copy of the game without its controllers, for a controller to choose a move in.
+    fn position(&self) -> Self {
+        Self {
+            board: self.board.clone(),
+            controllers: Default::default(),
+            current_player: self.current_player,
+            history: self.history.clone(),
+            record: Default::default(),
+            valid_moves: self.valid_moves.clone(),
+            last_analysis: None,
+        }
     }
 
     pub fn current_player(&self) -> Player {
         self.current_player
     }
 
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Every turn played so far, passes included.
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// The squares the current player can play on.
+    pub fn legal_moves(&self) -> Vec<(usize, usize)> {
+        Self::get_valid_moves_for_player(&self.board, self.current_player).collect()
+    }
+
+    pub fn is_legal(&self, coord: (usize, usize)) -> bool {
+        self.check_move(coord).is_ok()
+    }
+
+    /// Plays the current player's piece on `coord` and hands the turn over.
+    pub fn play(&mut self, coord: (usize, usize)) -> Result<(), MoveError> {
+        self.check_move(coord)?;
+        self.make_move(Move::Place(coord));
+        Ok(())
+    }
+
+    /// Plays `m` for the current player, as when replaying a game's history.
+    pub fn play_move(&mut self, m: Move) -> Result<(), MoveError> {
+        match m {
+            Move::Place(coord) => self.play(coord),
+            Move::Pass => self.pass(),
+        }
+    }
+
+    /// Hands the turn over when the current player has no legal move.
+    pub fn pass(&mut self) -> Result<(), MoveError> {
+        self.can_pass()?;
+        self.make_move(Move::Pass);
+        Ok(())
+    }
+
+    /// Takes back the last turn, whether a move or a pass.
+    pub fn undo(&mut self) -> Result<(), MoveError> {
+        if self.history.is_empty() {
+            return Err(MoveError::NothingToUndo);
+        }
+        self.undo_turn();
+        self.record.back();
+        self.update_valid_moves();
+        Ok(())
+    }
+
+    /// Plays the last turn taken back again, or the last one played from here if there were
+    /// several.
+    pub fn redo(&mut self) -> Result<(), MoveError> {
+        let (_, m) = self.record.redo_turn().ok_or(MoveError::NothingToRedo)?;
+        self.make_move(m);
+        Ok(())
+    }
+
+    /// Undoes or redoes turns until `ply` turns of the current line have been played (see
+    /// [`GameRecord::line`]).
+    pub fn goto(&mut self, ply: usize) -> Result<(), MoveError> {
+        if ply > self.record.line().len() {
+            return Err(MoveError::NoSuchPly(ply));
+        }
+        while self.record.ply() > ply {
+            self.undo()?;
+        }
+        while self.record.ply() < ply {
+            self.redo()?;
+        }
+        Ok(())
+    }
+
+    /// Undoes the last turn and then any more until a human is to move, so that a bot does not
+    /// simply play its move again. With only bots playing, undoes just the last turn.
+    pub fn undo_to_human_turn(&mut self) -> Result<(), MoveError> {
+        self.undo()?;
+        if self.controllers.iter().any(Controller::is_human) {
+            while !self.controller(self.current_player).is_human() && !self.history.is_empty() {
+                self.undo()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Every line the game has taken, for navigating it.
+    pub fn record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    /// The number of `player`'s pieces on the board.
+    pub fn score(&self, player: Player) -> usize {
+        self.board.count_for_player(player)
+    }
+
+    /// Whether neither player can move.
+    pub fn is_game_over(&self) -> bool {
+        !Self::anyone_can_move(&self.board)
+    }
+
+    fn can_pass(&self) -> Result<(), MoveError> {
+        if self.is_game_over() {
+            Err(MoveError::GameOver)
+        } else if Self::can_move(&self.board, self.current_player) {
+            Err(MoveError::CannotPass)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_move(&self, coord: (usize, usize)) -> Result<(), MoveError> {
+        if !self.board.contains(coord) {
+            Err(MoveError::OffBoard(coord))
+        } else if self.board.get(coord).is_some() {
+            Err(MoveError::Occupied(coord))
+        } else if self.is_game_over() {
+            Err(MoveError::GameOver)
+        } else if self.board.flips_for_move(coord, self.current_player) == 0 {
+            Err(MoveError::NoCaptures(coord))
+        } else {
+            Ok(())
+        }
+    }
+
     pub(crate) fn valid_moves(&self) -> &[(usize, usize)] {
         &self.valid_moves
     }
 
     pub(crate) fn undo_turn(&mut self) {
-        let (player, coord, captured_pieces) = self.history.pop().unwrap();
-        self.board.set(coord, None);
-        for coord in captured_pieces {
-            self.board.set(coord, Some(player.other()));
+        let (player, m, captured_pieces) =
+            self.history.pop().expect("undo_turn needs a move to undo");
+        if let Move::Place(coord) = m {
+            self.board.undo_move(coord, player, captured_pieces);
         }
         self.current_player = player;
     }
 
-    pub(crate) fn board(&self) -> &Board {
-        &self.board
+    /// Zobrist hash of the board and the player to move.
+    pub(crate) fn zobrist_key(&self) -> u64 {
+        match self.current_player {
+            Player::Green => self.board.zobrist_key(),
+            Player::Red => self.board.zobrist_key() ^ zobrist::RED_TO_MOVE_KEY,
+        }
     }
 
-    fn get_captures_for_position(board: &Board, coord: (usize, usize)) -> Vec<(usize, usize)> {
-        let player = board.get(coord);
-        if player.is_none() {
-            return vec![];
+    fn get_captures_for_position(board: &Board, coord: (usize, usize), player: Player) -> Bitboard {
+        if board.get(coord).is_some() {
+            return 0;
         }
 
-        let player = player.unwrap();
-        let n = board.size();
-        let in_bounds = |(a, b): (usize, usize)| a < n && b < n;
-        let mut res = Vec::new();
-
-        for (i, j) in DIRECTIONS {
-            let increment = |(a, b): (usize, usize)| (a.wrapping_add(i), b.wrapping_add(j));
-            let mut coord = increment(coord);
-            let mut v = Vec::new();
-            while in_bounds(coord) {
-                let p = board.get(coord);
-                match p {
-                    Some(p) if p == player => {
-                        res.append(&mut v);
-                        break;
-                    }
-                    Some(_) => v.push(coord),
-                    _ => {
-                        v.clear();
-                        break;
-                    }
-                }
-                coord = increment(coord);
-            }
-        }
-        res
+        board.flips_for_move(coord, player)
     }
 }
 
@@ -313,9 +755,183 @@ mod tests {
     fn test_get_captures_for_position() {
         let game = Reversi::new(None);
 
+        let captures = Reversi::get_captures_for_position(game.board(), (4, 2), Player::Green);
         assert_eq!(
-            Reversi::get_captures_for_position(game.board(), (4, 2)),
+            game.board().coords(captures).collect::<Vec<_>>(),
             vec![(4, 3)]
         );
     }
+
+    #[test]
+    fn test_bots_play_each_other() {
+        let mut game = Reversi::with_controllers(
+            Bot::from_difficulty(
+                BotDifficulty::Easy,
+                BotAlgorithm::AlphaBeta,
+                BotHeuristic::TacticalWeighting,
+            )
+            .into(),
+            Bot::from_difficulty(
+                BotDifficulty::Medium,
+                BotAlgorithm::NegaMax,
+                BotHeuristic::UniformWeighting,
+            )
+            .into(),
+        );
+        let winner = game.play_to_end().unwrap();
+        assert!(!Reversi::anyone_can_move(game.board()));
+        assert_eq!(winner, game.get_winner());
+
+        let mut game = Reversi::new(Some((
+            Player::Red,
+            BotDifficulty::Easy,
+            BotAlgorithm::MinMax,
+            BotHeuristic::UniformWeighting,
+        )));
+        assert!(matches!(
+            game.play_to_end(),
+            Err(Error::HumanToMove(Player::Green))
+        ));
+    }
+
+    #[test]
+    fn test_other_board_sizes() {
+        for (size, heuristic) in [
+            (6, BotHeuristic::TacticalWeighting),
+            (6, BotHeuristic::Pattern),
+            (10, BotHeuristic::Stability),
+            (10, BotHeuristic::Composite(Default::default())),
+        ] {
+            let bot =
+                || Bot::from_difficulty(BotDifficulty::Easy, BotAlgorithm::AlphaBeta, heuristic);
+            let mut game =
+                Reversi::with_controllers(bot().into(), bot().into()).with_board_size(size);
+            game.update_valid_moves();
+            let mid = size / 2;
+            assert_eq!(
+                HashSet::<(usize, usize)>::from_iter(game.valid_moves.iter().copied()),
+                HashSet::from_iter([
+                    (mid - 2, mid),
+                    (mid, mid - 2),
+                    (mid - 1, mid + 1),
+                    (mid + 1, mid - 1)
+                ])
+            );
+
+            game.play_to_end().unwrap();
+            assert!(!Reversi::anyone_can_move(game.board()));
+            let pieces = game.board().count_for_player(Player::Green)
+                + game.board().count_for_player(Player::Red);
+            assert!(pieces > size * size / 2 && pieces <= size * size);
+        }
+    }
+
+    #[test]
+    fn test_rejects_unsupported_board_size() {
+        assert!(matches!(
+            Reversi::new(None).try_with_board_size(9),
+            Err(Error::InvalidBoardSize(9))
+        ));
+    }
+
+    #[test]
+    fn test_position_api() {
+        // Red's only piece is on the first row, with nowhere to go, while Green can take it
+        let mut game =
+            Reversi::from_position_str(&format!("{}-OXXXXXX O", "-".repeat(56))).unwrap();
+
+        assert!(game.legal_moves().is_empty());
+        assert!(game.hint().is_err());
+        assert_eq!(game.play((0, 0)), Err(MoveError::NoCaptures((0, 0))));
+        game.play_move(Move::Pass).unwrap();
+        assert_eq!(game.pass(), Err(MoveError::CannotPass));
+        assert_eq!(game.legal_moves(), vec![(0, 0)]);
+        assert_eq!(game.hint().unwrap(), (0, 0));
+        assert_eq!(game.play((0, 2)), Err(MoveError::Occupied((0, 2))));
+        assert_eq!(game.play((8, 0)), Err(MoveError::OffBoard((8, 0))));
+
+        game.play((0, 0)).unwrap();
+        assert!(game.is_game_over());
+        assert_eq!((game.score(Player::Green), game.score(Player::Red)), (8, 0));
+        assert_eq!(game.get_winner(), Some(Player::Green));
+        assert_eq!(game.pass(), Err(MoveError::GameOver));
+
+        assert_eq!(
+            game.history().moves().collect::<Vec<_>>(),
+            vec![
+                (Player::Red, Move::Pass),
+                (Player::Green, Move::Place((0, 0)))
+            ]
+        );
+
+        game.undo().unwrap();
+        assert_eq!(game.current_player(), Player::Green);
+        assert_eq!(game.board().get((0, 1)), Some(Player::Red));
+        game.undo().unwrap();
+        assert_eq!(game.current_player(), Player::Red);
+        assert_eq!(game.undo(), Err(MoveError::NothingToUndo));
+    }
+
+    #[test]
+    fn test_position_strings() {
+        let start = "---------------------------OX------XO--------------------------- X";
+        assert_eq!(Reversi::new(None).to_position_string(), start);
+        let game = Reversi::from_position_str(start).unwrap();
+        assert_eq!(game.board(), &Board::new(8));
+        assert_eq!(game.current_player(), Player::Green);
+
+        let game = Reversi::from_position_str(&format!("{}ox{} o", ".".repeat(14), "-".repeat(20)))
+            .unwrap();
+        assert_eq!(game.board().size(), 6);
+        assert_eq!(game.board().get((3, 2)), Some(Player::Red));
+        assert_eq!(game.current_player(), Player::Red);
+        assert_eq!(
+            game.to_position_string(),
+            format!("{}OX{} O", "-".repeat(14), "-".repeat(20))
+        );
+
+        for (position, reason) in [
+            ("", "the position is empty"),
+            (&start[..start.len() - 1], "unexpected player to move '-'"),
+            (&start[1..], "63 squares do not make a square board"),
+            (&start.replace('X', "Z"), "unexpected player to move 'Z'"),
+            (&start.replacen('X', "Z", 1), "unexpected square 'Z'"),
+        ] {
+            assert!(matches!(
+                Reversi::from_position_str(position),
+                Err(Error::InvalidPosition(error)) if error == reason
+            ));
+        }
+    }
+
+    #[cfg(feature = "terminal")]
+    #[test]
+    fn test_terminal_move_errors() {
+        let game = Reversi::new(None);
+        assert_eq!(
+            game.describe_move_error(game.check_move((3, 3)).unwrap_err()),
+            "d5 is already taken"
+        );
+        assert_eq!(
+            game.describe_move_error(game.check_move((7, 0)).unwrap_err()),
+            "a1 would flip no pieces"
+        );
+        assert!(Reversi::looks_like_square("i9"));
+        assert!(Reversi::looks_like_square("A10"));
+        assert!(!Reversi::looks_like_square("hint"));
+        assert!(!Reversi::looks_like_square("d"));
+    }
+
+    #[test]
+    fn test_zobrist_key_restored_by_undo_turn() {
+        let mut game = Reversi::new(None);
+        let key = game.zobrist_key();
+
+        game.place_piece_and_add_history((2, 4));
+        game.switch_players();
+        assert_ne!(game.zobrist_key(), key);
+
+        game.undo_turn();
+        assert_eq!(game.zobrist_key(), key);
+    }
 }
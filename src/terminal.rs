@@ -0,0 +1,90 @@
+use std::io::{self, IsTerminal};
+
+use strum::IntoEnumIterator;
+use text_io::read;
+
+use crate::{
+    board_size::BoardSize,
+    bot::Bot,
+    bot_algorithm::BotAlgorithm,
+    bot_difficulty::BotDifficulty,
+    bot_heuristic::BotHeuristic,
+    controller::{Controller, ControllerKind, ExternalEngine},
+    move_provider::MoveProvider,
+    player::Player,
+    reversi::Reversi,
+    tui,
+    utils::{clear_terminal, prompt_choice, prompt_name},
+};
+
+/// Plays a game in the terminal, offering `providers` alongside the built-in controllers.
+pub fn run(providers: Vec<Box<dyn MoveProvider>>) {
+    let [green, red] = prompt_controllers(providers);
+    let size: BoardSize = prompt_choice("Enter a board size");
+    play(Reversi::with_controllers(green, red).with_board_size(size.squares_per_side()))
+}
+
+/// Plays `game` in the terminal from its position, asking who plays each side and offering
+/// `providers` alongside the built-in controllers.
+pub fn run_from(providers: Vec<Box<dyn MoveProvider>>, mut game: Reversi) {
+    let [green, red] = prompt_controllers(providers);
+    *game.controller_mut(Player::Green) = green;
+    *game.controller_mut(Player::Red) = red;
+    play(game)
+}
+
+/// Plays `game` full screen, or turn by turn on standard input and output when either is
+/// not a terminal, so that games can still be scripted.
+pub fn play(mut game: Reversi) {
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return game.start();
+    }
+    if let Err(error) = tui::run(game) {
+        eprintln!("ERROR: {}", error);
+    }
+}
+
+/// Asks who plays each side, Green first.
+fn prompt_controllers(providers: Vec<Box<dyn MoveProvider>>) -> [Controller; 2] {
+    let kinds: Vec<ControllerKind> = ControllerKind::iter()
+        .filter(|&kind| kind != ControllerKind::Custom)
+        .collect();
+    let names: Vec<String> = kinds
+        .iter()
+        .map(|kind| kind.to_string())
+        .chain(providers.iter().map(|provider| provider.name()))
+        .collect();
+    let providers: Vec<Controller> = providers.into_iter().map(Controller::from).collect();
+
+    [Player::Green, Player::Red].map(|player| {
+        let choice = prompt_name(&format!("Who plays {}?", player), &names);
+        match kinds.get(choice) {
+            Some(ControllerKind::Human) => Controller::Human,
+            Some(ControllerKind::Bot) => {
+                let difficulty: BotDifficulty = prompt_choice("Enter a difficulty");
+                let algorithm: BotAlgorithm = prompt_choice("Enter an algorithm");
+                let heuristic: BotHeuristic = prompt_choice("Enter a heuristic method");
+                Bot::from_difficulty(difficulty, algorithm, heuristic).into()
+            }
+            Some(ControllerKind::Engine) => prompt_engine(),
+            _ => providers[choice - kinds.len()].clone(),
+        }
+    })
+}
+
+fn prompt_engine() -> Controller {
+    loop {
+        println!("Enter the command that starts the engine:");
+        let line: String = read!("{}\n");
+        let mut words = line.split_whitespace();
+        let Some(program) = words.next() else {
+            continue;
+        };
+        let args: Vec<_> = words.collect();
+        clear_terminal();
+        match ExternalEngine::spawn(program, &args) {
+            Ok(engine) => break Controller::Engine(engine),
+            Err(error) => println!("ERROR: {}", error),
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use std::{collections::HashSet, fmt, str::FromStr, time::Duration};
+
+use web_time::Instant;
+
+use crate::{
+    bot::{self, Bot},
+    bot_algorithm::BotAlgorithm,
+    bot_heuristic::{BotHeuristic, CompositeWeights},
+    history::Move,
+    player::Player,
+    reversi::Reversi,
+};
+
+/// Openings are picked among the positions this many moves into the game.
+const OPENING_PLIES: usize = 4;
+
+/// Depth of the search that judges how balanced an opening is.
+const OPENING_EVAL_DEPTH: usize = 3;
+
+/// Search depth of contestants given neither a depth nor a time limit.
+pub const DEFAULT_DEPTH: usize = 6;
+
+/// Two standard errors either side of a result make a 95% confidence interval.
+const CONFIDENCE_Z: f64 = 1.96;
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumIter, strum::EnumString, Debug)]
+pub enum TournamentFormat {
+    /// Every contestant plays every other.
+    RoundRobin,
+    /// The first contestant plays each of the others.
+    Gauntlet,
+}
+
+/// A bot configuration taking part in a tournament.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Contestant {
+    pub algorithm: BotAlgorithm,
+    pub heuristic: BotHeuristic,
+    pub max_depth: Option<usize>,
+    pub time_limit: Option<Duration>,
+}
+
+impl Contestant {
+    pub fn bot(&self) -> Bot {
+        Bot::new(self.algorithm, self.max_depth, self.heuristic).with_time_limit(self.time_limit)
+    }
+
+    /// Limits a contestant that has neither a depth nor a time limit to [`DEFAULT_DEPTH`],
+    /// so its searches finish.
+    pub fn with_default_limit(mut self) -> Self {
+        if self.max_depth.is_none() && self.time_limit.is_none() {
+            self.max_depth = Some(DEFAULT_DEPTH);
+        }
+        self
+    }
+}
+
+impl fmt::Display for Contestant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        bot::write_settings(
+            f,
+            self.algorithm,
+            self.heuristic,
+            self.max_depth,
+            self.time_limit,
+        )
+    }
+}
+
+/// Parses `ALGORITHM:HEURISTIC[:DEPTH][:TIMEms]`, e.g. `AlphaBeta:TacticalWeighting:8` or
+/// `NegaMax:UniformWeighting:500ms`. Without either limit the depth is [`DEFAULT_DEPTH`].
+impl FromStr for Contestant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let algorithm = parts.next().unwrap_or_default();
+        let algorithm = algorithm
+            .parse()
+            .map_err(|_| format!("unknown algorithm '{algorithm}'"))?;
+        let heuristic = parts.next().ok_or(format!("'{s}' has no heuristic"))?;
+        let heuristic = heuristic
+            .parse()
+            .map_err(|_| format!("unknown heuristic '{heuristic}'"))?;
+
+        let mut contestant = Self {
+            algorithm,
+            heuristic,
+            max_depth: None,
+            time_limit: None,
+        };
+        for limit in parts {
+            if let Some(millis) = limit.strip_suffix("ms") {
+                let millis = millis
+                    .parse()
+                    .map_err(|_| format!("invalid time limit '{limit}'"))?;
+                contestant.time_limit = Some(Duration::from_millis(millis));
+            } else {
+                let depth = limit
+                    .parse()
+                    .map_err(|_| format!("invalid depth '{limit}'"))?;
+                contestant.max_depth = Some(depth);
+            }
+        }
+        Ok(contestant.with_default_limit())
+    }
+}
+
+/// Wins, draws and losses of one contestant against another, or against the whole field.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct Record {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+impl Record {
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Points per game, counting a draw as half a win.
+    pub fn score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+    }
+
+    /// The Elo difference to the opponents implied by the score, with the 95% confidence
+    /// interval around it as `(low, estimate, high)`. Perfect scores give infinite values.
+    pub fn elo(&self) -> (f64, f64, f64) {
+        let games = self.games() as f64;
+        let score = self.score();
+        let variance = (self.wins as f64 * (1.0 - score).powi(2)
+            + self.draws as f64 * (0.5 - score).powi(2)
+            + self.losses as f64 * score.powi(2))
+            / games;
+        let margin = CONFIDENCE_Z * (variance / games).sqrt();
+        (
+            elo_difference(score - margin),
+            elo_difference(score),
+            elo_difference(score + margin),
+        )
+    }
+
+    fn flipped(&self) -> Self {
+        Self {
+            wins: self.losses,
+            draws: self.draws,
+            losses: self.wins,
+        }
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+    }
+}
+
+fn elo_difference(score: f64) -> f64 {
+    -400.0 * (1.0 / score.clamp(0.0, 1.0) - 1.0).log10()
+}
+
+/// Search effort over all of a contestant's moves.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct MoveStats {
+    pub moves: usize,
+    pub nodes: usize,
+    pub time: Duration,
+}
+
+impl MoveStats {
+    pub fn nodes_per_move(&self) -> f64 {
+        self.nodes as f64 / self.moves.max(1) as f64
+    }
+
+    pub fn time_per_move(&self) -> Duration {
+        self.time / self.moves.max(1) as u32
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct TournamentResult {
+    pub contestants: Vec<Contestant>,
+    /// `records[a][b]` is how contestant `a` did against contestant `b`.
+    pub records: Vec<Vec<Record>>,
+    pub stats: Vec<MoveStats>,
+}
+
+impl TournamentResult {
+    /// How a contestant did against everyone it played.
+    pub fn total(&self, contestant: usize) -> Record {
+        let mut total = Record::default();
+        for record in &self.records[contestant] {
+            total.add(record);
+        }
+        total
+    }
+}
+
+impl fmt::Display for TournamentResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<_> = self.contestants.iter().map(ToString::to_string).collect();
+        let width = names.iter().map(String::len).max().unwrap_or_default();
+        let format_elo = |record: &Record| {
+            let (low, elo, high) = record.elo();
+            format!("{elo:+.0} [{low:+.0}, {high:+.0}]")
+        };
+
+        writeln!(f, "Matches (W/D/L, Elo with 95% confidence interval):")?;
+        for (a, records) in self.records.iter().enumerate() {
+            for (b, record) in records.iter().enumerate() {
+                if a < b && record.games() > 0 {
+                    writeln!(
+                        f,
+                        "  {:width$}  vs  {:width$}  {}/{}/{}  {}",
+                        names[a],
+                        names[b],
+                        record.wins,
+                        record.draws,
+                        record.losses,
+                        format_elo(record)
+                    )?;
+                }
+            }
+        }
+
+        writeln!(f, "\nStandings:")?;
+        writeln!(
+            f,
+            "  {:width$}  {:>5}  {:>5}  {:>5}  {:>30}  {:>12}  {:>12}",
+            "Bot", "W", "D", "L", "Elo vs opponents", "nodes/move", "ms/move"
+        )?;
+        for (i, name) in names.iter().enumerate() {
+            let total = self.total(i);
+            writeln!(
+                f,
+                "  {:width$}  {:>5}  {:>5}  {:>5}  {:>30}  {:>12.0}  {:>12.1}",
+                name,
+                total.wins,
+                total.draws,
+                total.losses,
+                format_elo(&total),
+                self.stats[i].nodes_per_move(),
+                self.stats[i].time_per_move().as_secs_f64() * 1000.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Plays each pairing of `format` from every opening, once with each colour.
+pub fn run(
+    contestants: &[Contestant],
+    format: TournamentFormat,
+    openings: &[Vec<(usize, usize)>],
+) -> TournamentResult {
+    let mut result = TournamentResult {
+        contestants: contestants.to_vec(),
+        records: vec![vec![Record::default(); contestants.len()]; contestants.len()],
+        stats: vec![MoveStats::default(); contestants.len()],
+    };
+
+    let pairings: Vec<_> = match format {
+        TournamentFormat::RoundRobin => (0..contestants.len())
+            .flat_map(|a| (a + 1..contestants.len()).map(move |b| (a, b)))
+            .collect(),
+        TournamentFormat::Gauntlet => (1..contestants.len()).map(|b| (0, b)).collect(),
+    };
+    for (a, b) in pairings {
+        for opening in openings {
+            for (green, red) in [(a, b), (b, a)] {
+                let winner = play_game(contestants, green, red, opening, &mut result.stats);
+                let record = Record {
+                    wins: (winner == Some(Player::Green)) as usize,
+                    draws: winner.is_none() as usize,
+                    losses: (winner == Some(Player::Red)) as usize,
+                };
+                result.records[green][red].add(&record);
+                result.records[red][green].add(&record.flipped());
+            }
+        }
+    }
+    result
+}
+
+/// Plays one game from `opening` and returns the winner.
+fn play_game(
+    contestants: &[Contestant],
+    green: usize,
+    red: usize,
+    opening: &[(usize, usize)],
+    stats: &mut [MoveStats],
+) -> Option<Player> {
+    let mut game = Reversi::with_controllers(
+        contestants[green].bot().into(),
+        contestants[red].bot().into(),
+    );
+    game.update_valid_moves();
+    for &coord in opening {
+        game.make_move(Move::Place(coord));
+    }
+    while Reversi::anyone_can_move(game.board()) {
+        if !game.valid_moves().is_empty() {
+            let player = game.current_player();
+            let start = Instant::now();
+            let coord = game
+                .controller_move()
+                .and_then(Result::ok)
+                .expect("bots always choose a move");
+            let metrics = game.controller(player).as_bot().unwrap().get_metrics();
+            let stats = &mut stats[if player == Player::Green { green } else { red }];
+            stats.moves += 1;
+            stats.nodes += metrics.nodes();
+            stats.time += start.elapsed();
+            game.make_move(Move::Place(coord));
+        } else {
+            game.make_move(Move::Pass);
+        }
+    }
+    game.get_winner()
+}
+
+/// The `count` most balanced positions a few moves into the game, as the moves reaching them,
+/// judged by a shallow search.
+pub fn balanced_openings(count: usize) -> Vec<Vec<(usize, usize)>> {
+    let mut lines = vec![(Reversi::new(None), Vec::new())];
+    for _ in 0..OPENING_PLIES {
+        let mut seen = HashSet::new();
+        let mut next = Vec::new();
+        for (game, line) in lines {
+            for coord in Reversi::get_valid_moves_for_player(game.board(), game.current_player()) {
+                let mut child = game.clone();
+                child.place_piece_and_add_history(coord);
+                child.switch_players();
+                if seen.insert(child.board().clone()) {
+                    let mut child_line: Vec<_> = line.clone();
+                    child_line.push(coord);
+                    next.push((child, child_line));
+                }
+            }
+        }
+        lines = next;
+    }
+
+    let mut judge = Bot::new(
+        BotAlgorithm::NegaMax,
+        Some(OPENING_EVAL_DEPTH),
+        BotHeuristic::Composite(CompositeWeights::default()),
+    );
+    let mut scored: Vec<_> = lines
+        .into_iter()
+        .map(|(mut game, line)| {
+            game.update_valid_moves();
+            let (score, _) = judge.negamax(&mut game, 0, -i64::MAX, i64::MAX);
+            (score.abs(), line)
+        })
+        .collect();
+    scored.sort_by_key(|(imbalance, _)| *imbalance);
+    scored
+        .into_iter()
+        .take(count)
+        .map(|(_, line)| line)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elo_estimate() {
+        let record = Record {
+            wins: 60,
+            draws: 30,
+            losses: 10,
+        };
+        let (low, elo, high) = record.elo();
+        assert!((elo - 190.85).abs() < 0.01);
+        assert!(low < elo && elo < high);
+        assert_eq!(Record::default().flipped(), Record::default());
+    }
+
+    #[test]
+    fn test_contestants_round_trip() {
+        for text in [
+            "AlphaBeta:TacticalWeighting:8",
+            "NegaMax:UniformWeighting:500ms",
+            "Pvs:Composite:6:250ms",
+        ] {
+            let contestant: Contestant = text.parse().unwrap();
+            assert_eq!(contestant.to_string(), text);
+        }
+        let composite: Contestant = "MtdF:Composite".parse().unwrap();
+        assert_eq!(
+            composite.heuristic,
+            BotHeuristic::Composite(CompositeWeights::default())
+        );
+        assert_eq!(composite.max_depth, Some(DEFAULT_DEPTH));
+    }
+
+    #[test]
+    fn test_round_robin_alternates_colours() {
+        let contestants: Vec<Contestant> = [
+            "NegaMax:UniformWeighting:1",
+            "AlphaBeta:TacticalWeighting:2",
+            "NegaMax:Mobility:1",
+        ]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+        let openings = balanced_openings(2);
+        assert_eq!(openings.len(), 2);
+
+        let result = run(&contestants, TournamentFormat::RoundRobin, &openings);
+        for a in 0..3 {
+            assert_eq!(result.total(a).games(), 2 * 2 * 2);
+            for b in 0..3 {
+                assert_eq!(result.records[a][b], result.records[b][a].flipped());
+            }
+            assert!(result.stats[a].moves > 0);
+        }
+        assert_eq!(result.records[0][0].games(), 0);
+
+        let gauntlet = run(&contestants, TournamentFormat::Gauntlet, &openings);
+        assert_eq!(gauntlet.records[1][2].games(), 0);
+        assert_eq!(gauntlet.total(0).games(), 2 * 2 * 2);
+    }
+}
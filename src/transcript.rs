@@ -0,0 +1,383 @@
+use std::{fmt, fs, io, path::Path, str::FromStr};
+
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    board::Board,
+    history::Move,
+    player::Player,
+    reversi::{MoveError, Reversi},
+};
+
+/// A whole game as PGN-style tags followed by its moves in standard Othello notation, such as
+///
+/// ```text
+/// [Event "Reversi"]
+/// [Date "2024.05.01"]
+/// [Green "Human"]
+/// [Red "Bot AlphaBeta:TacticalWeighting:8"]
+/// [Size "8"]
+/// [Result "*"]
+///
+/// f5d6c3d3c4
+/// ```
+///
+/// Squares are a column letter and a row number, with row 1 at the bottom so that Green (who
+/// moves first) plays the standard first moves d3, c4, f5 and e6. Passes are left out as
+/// usual, and put back wherever the player to move has no legal move, though `pa` is also
+/// read as one. Games set up from another position have it in a `Position` tag, in the format
+/// of [`Reversi::from_position_str`].
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct Transcript {
+    /// Tag names and values, in the order they are written.
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<Move>,
+}
+
+#[derive(Debug)]
+pub enum TranscriptError {
+    Io(io::Error),
+    /// A line starting with `[` is not a `[Name "value"]` tag.
+    InvalidTag(String),
+    InvalidSize(String),
+    /// The `Position` tag could not be read, for the reason given.
+    InvalidPosition(String),
+    InvalidSquare(String),
+    IllegalMove {
+        /// The number of the move, counting from 1 and leaving out passes.
+        number: usize,
+        square: String,
+        error: MoveError,
+    },
+}
+
+impl fmt::Display for TranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not read or write the transcript: {error}"),
+            Self::InvalidTag(line) => write!(f, "invalid tag '{line}'"),
+            Self::InvalidSize(size) => write!(f, "invalid board size '{size}'"),
+            Self::InvalidPosition(reason) => write!(f, "invalid starting position: {reason}"),
+            Self::InvalidSquare(square) => write!(f, "invalid square '{square}'"),
+            Self::IllegalMove {
+                number,
+                square,
+                error,
+            } => write!(f, "move {number} ({square}) is illegal: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for TranscriptError {}
+
+impl From<io::Error> for TranscriptError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl Transcript {
+    /// The moves played to reach `game`'s current position, tagged with today's date, who
+    /// plays each side, the result if the game is over and the starting position if it was
+    /// not the usual one.
+    pub fn from_game(game: &Reversi) -> Self {
+        let mut start = game.clone();
+        start.goto(0).unwrap();
+        let result = if game.is_game_over() {
+            format!("{}-{}", game.score(Player::Green), game.score(Player::Red))
+        } else {
+            String::from("*")
+        };
+        let mut tags = vec![
+            ("Event", String::from("Reversi")),
+            ("Date", today()),
+            ("Green", game.controller(Player::Green).to_string()),
+            ("Red", game.controller(Player::Red).to_string()),
+            ("Size", game.board().size().to_string()),
+            ("Result", result),
+        ];
+        if start.board() != &Board::new(game.board().size())
+            || start.current_player() != Player::Green
+        {
+            tags.push(("Position", start.to_position_string()));
+        }
+        Self {
+            tags: tags
+                .into_iter()
+                .map(|(name, value)| (String::from(name), value))
+                .collect(),
+            moves: game.history().moves().map(|(_, m)| m).collect(),
+        }
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The board size from the `Size` tag, 8 without one.
+    pub fn size(&self) -> Result<usize, TranscriptError> {
+        match self.tag("Size") {
+            Some(size) => size
+                .parse()
+                .map_err(|_| TranscriptError::InvalidSize(String::from(size))),
+            None => Ok(8),
+        }
+    }
+
+    /// Starts `game` over and plays the transcript's moves in it, keeping its controllers. The
+    /// game is left as it was if a move is illegal.
+    pub fn replay(&self, game: &mut Reversi) -> Result<(), TranscriptError> {
+        let size = self.size()?;
+        let mut replayed = match self.tag("Position") {
+            Some(position) => {
+                let start = Reversi::from_position_str(position)
+                    .map_err(|error| TranscriptError::InvalidPosition(error.to_string()))?;
+                if start.board().size() != size {
+                    return Err(TranscriptError::InvalidPosition(format!(
+                        "the position is not on a {size}x{size} board"
+                    )));
+                }
+                game.clone()
+                    .with_position(start.board().clone(), start.current_player())
+            }
+            None => game
+                .clone()
+                .try_with_board_size(size)
+                .map_err(|_| TranscriptError::InvalidSize(size.to_string()))?,
+        };
+        replayed.update_valid_moves();
+
+        let mut number = 0;
+        for &m in &self.moves {
+            if let Move::Place(coord) = m {
+                number += 1;
+                // passes are left out of most transcripts
+                while replayed.legal_moves().is_empty() && !replayed.is_game_over() {
+                    replayed.pass().unwrap();
+                }
+                replayed
+                    .play(coord)
+                    .map_err(|error| TranscriptError::IllegalMove {
+                        number,
+                        square: square_name(coord, size),
+                        error,
+                    })?;
+            } else if replayed.pass().is_err() {
+                return Err(TranscriptError::IllegalMove {
+                    number: number + 1,
+                    square: String::from("pa"),
+                    error: MoveError::CannotPass,
+                });
+            }
+        }
+        *game = replayed;
+        Ok(())
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), TranscriptError> {
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, TranscriptError> {
+        fs::read_to_string(path)?.parse()
+    }
+}
+
+impl fmt::Display for Transcript {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in &self.tags {
+            writeln!(f, "[{name} \"{}\"]", value.replace('"', "'"))?;
+        }
+        writeln!(f)?;
+        let size = self.size().unwrap_or(8);
+        for m in &self.moves {
+            if let Move::Place(coord) = m {
+                write!(f, "{}", square_name(*coord, size))?;
+            }
+        }
+        writeln!(f)
+    }
+}
+
+impl FromStr for Transcript {
+    type Err = TranscriptError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut transcript = Self::default();
+        let mut move_text = String::new();
+        for line in s.lines().map(str::trim) {
+            if line.starts_with('[') {
+                let tag = line
+                    .strip_prefix('[')
+                    .and_then(|tag| tag.strip_suffix(']'))
+                    .and_then(|tag| tag.split_once(' '))
+                    .and_then(|(name, value)| {
+                        let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+                        Some((String::from(name), String::from(value)))
+                    })
+                    .ok_or_else(|| TranscriptError::InvalidTag(String::from(line)))?;
+                transcript.tags.push(tag);
+            } else {
+                move_text.extend(line.chars().filter(|c| !c.is_whitespace()));
+            }
+        }
+
+        let size = transcript.size()?;
+        let mut rest = move_text.as_str();
+        while !rest.is_empty() {
+            let end = rest
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| !c.is_ascii_digit())
+                .map_or(rest.len(), |(i, _)| i);
+            let (square, tail) = if rest.get(..2).is_some_and(|s| s.eq_ignore_ascii_case("pa")) {
+                rest.split_at(2)
+            } else {
+                rest.split_at(end)
+            };
+            transcript.moves.push(if square.eq_ignore_ascii_case("pa") {
+                Move::Pass
+            } else {
+                Move::Place(
+                    parse_square(square, size)
+                        .ok_or_else(|| TranscriptError::InvalidSquare(String::from(square)))?,
+                )
+            });
+            rest = tail;
+        }
+        Ok(transcript)
+    }
+}
+
+/// `coord` in standard notation, such as `f5`: the column as a letter from `a` on the left,
+/// then the row as a number from 1 at the bottom.
+pub fn square_name((row, col): (usize, usize), size: usize) -> String {
+    format!("{}{}", (b'a' + col as u8) as char, size - row)
+}
+
+/// The square named `name` in standard notation, in either case, if it is on the board.
+pub fn parse_square(name: &str, size: usize) -> Option<(usize, usize)> {
+    let mut chars = name.chars();
+    let col = chars.next()?.to_ascii_lowercase();
+    let number: usize = chars.as_str().parse().ok()?;
+    let col = (col as usize).checked_sub('a' as usize)?;
+    (col < size && (1..=size).contains(&number)).then(|| (size - number, col))
+}
+
+/// Today's date as `YYYY.MM.DD`, in UTC.
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() / 86_400) as i64;
+    // the proleptic Gregorian calendar counted in 400-year eras from 0000-03-01
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}.{month:02}.{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bot::Bot, bot_algorithm::BotAlgorithm, bot_difficulty::BotDifficulty,
+        bot_heuristic::BotHeuristic, controller::Controller,
+    };
+
+    #[test]
+    fn test_transcripts_round_trip() {
+        for size in [6, 8, 10] {
+            let bot = || {
+                Bot::from_difficulty(
+                    BotDifficulty::Easy,
+                    BotAlgorithm::AlphaBeta,
+                    BotHeuristic::TacticalWeighting,
+                )
+            };
+            let mut game =
+                Reversi::with_controllers(bot().into(), bot().into()).with_board_size(size);
+            game.play_to_end().unwrap();
+
+            let transcript = Transcript::from_game(&game);
+            let text = transcript.to_string();
+            let parsed: Transcript = text.parse().unwrap();
+            assert_eq!(parsed.tags, transcript.tags);
+            assert_eq!(
+                parsed.tag("Result"),
+                Some(format!("{}-{}", game.score(Player::Green), game.score(Player::Red)).as_str())
+            );
+
+            let mut replayed = Reversi::with_controllers(Controller::Human, Controller::Human);
+            parsed.replay(&mut replayed).unwrap();
+            assert_eq!(replayed.board(), game.board());
+            assert_eq!(
+                replayed.history().moves().collect::<Vec<_>>(),
+                game.history().moves().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_standard_transcripts_replay() {
+        let transcript: Transcript = "[Event \"Tiger\"]\nF5d6C3D3c4\npa".parse().unwrap();
+        assert_eq!(transcript.moves.len(), 6);
+
+        let mut game = Reversi::new(None);
+        let error = transcript.replay(&mut game).unwrap_err();
+        assert!(matches!(
+            error,
+            TranscriptError::IllegalMove {
+                number: 6,
+                error: MoveError::CannotPass,
+                ..
+            }
+        ));
+        assert_eq!(game.history().len(), 0);
+
+        let transcript: Transcript = "f5d6c3d3c4".parse().unwrap();
+        transcript.replay(&mut game).unwrap();
+        assert_eq!(game.history().len(), 5);
+        assert_eq!(
+            game.board().get(parse_square("c4", 8).unwrap()),
+            Some(Player::Green)
+        );
+
+        // a set-up position goes into a tag and is replayed from
+        let mut game =
+            Reversi::from_position_str(&format!("{}-OXXXXXX O", "-".repeat(56))).unwrap();
+        game.pass().unwrap();
+        game.play((0, 0)).unwrap();
+        let transcript: Transcript = Transcript::from_game(&game).to_string().parse().unwrap();
+        assert_eq!(
+            transcript.tag("Position"),
+            Some(format!("{}-OXXXXXX O", "-".repeat(56)).as_str())
+        );
+        assert_eq!(transcript.tag("Result"), Some("8-0"));
+        let mut replayed = Reversi::new(None);
+        transcript.replay(&mut replayed).unwrap();
+        assert_eq!(replayed.board(), game.board());
+
+        assert!(matches!(
+            "f5i9".parse::<Transcript>(),
+            Err(TranscriptError::InvalidSquare(square)) if square == "i9"
+        ));
+        assert!(matches!(
+            "f5f5".parse::<Transcript>().unwrap().replay(&mut game),
+            Err(TranscriptError::IllegalMove { number: 2, .. })
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+/// A searched position. Scores are from the point of view of the player to move.
+#[derive(Clone, Copy, Debug)]
+pub struct TranspositionEntry {
+    pub key: u64,
+    pub depth: usize,
+    pub score: i64,
+    pub bound: Bound,
+    pub best_move: Option<(usize, usize)>,
+    age: u8,
+}
+
+impl TranspositionEntry {
+    /// The stored score, if it was searched deep enough to decide a node searched with
+    /// `depth` plies remaining in the window `(alpha, beta)`.
+    pub(crate) fn cutoff(&self, depth: usize, alpha: i64, beta: i64) -> Option<i64> {
+        if self.depth < depth.min(u8::MAX as usize) {
+            return None;
+        }
+
+        match self.bound {
+            Bound::Exact => Some(self.score),
+            Bound::Lower if self.score >= beta => Some(self.score),
+            Bound::Upper if self.score <= alpha => Some(self.score),
+            _ => None,
+        }
+    }
+
+    /// Packs everything but the key into one word: the score in the low 32 bits, then the
+    /// depth, bound, best move and age, with the top bit marking the slot as used.
+    fn pack(&self) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 0,
+            Bound::Lower => 1,
+            Bound::Upper => 2,
+        };
+        let best_move = self
+            .best_move
+            .map_or(0, |(row, col)| 1 | (row as u64) << 1 | (col as u64) << 5);
+        self.score as i32 as u32 as u64
+            | (self.depth.min(u8::MAX as usize) as u64) << 32
+            | bound << 40
+            | best_move << 42
+            | (self.age as u64) << 51
+            | 1 << 63
+    }
+
+    fn unpack(key: u64, data: u64) -> Self {
+        let bound = match (data >> 40) & 0b11 {
+            0 => Bound::Exact,
+            1 => Bound::Lower,
+            _ => Bound::Upper,
+        };
+        let best_move = (data >> 42 & 1 == 1)
+            .then_some(((data >> 43 & 0xF) as usize, (data >> 47 & 0xF) as usize));
+        Self {
+            key,
+            depth: (data >> 32 & 0xFF) as usize,
+            score: data as u32 as i32 as i64,
+            bound,
+            best_move,
+            age: (data >> 51) as u8,
+        }
+    }
+}
+
+/// Fixed-size table indexed by the low bits of a position's Zobrist key.
+///
+/// A slot is overwritten by a deeper search of any position, or by any search once its
+/// entry is left over from a previous move.
+///
+/// Slots hold the key XORed with the packed entry next to the entry itself, so threads
+/// sharing the table (see [`TranspositionTable::share`]) can read and write it without
+/// locking: a slot torn by two writes no longer matches any key and reads as empty.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    slots: Arc<[[AtomicU64; 2]]>,
+    age: u8,
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(1 << 16)
+    }
+}
+
+/// Copies the entries into a table of its own.
+impl Clone for TranspositionTable {
+    fn clone(&self) -> Self {
+        Self {
+            slots: self
+                .slots
+                .iter()
+                .map(|slot| {
+                    slot.each_ref()
+                        .map(|word| word.load(Ordering::Relaxed).into())
+                })
+                .collect(),
+            age: self.age,
+        }
+    }
+}
+
+impl TranspositionTable {
+    /// Creates a table holding `capacity` entries, rounded up to a power of two.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            slots: (0..capacity.max(1).next_power_of_two())
+                .map(|_| Default::default())
+                .collect(),
+            age: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn clear(&mut self) {
+        for word in self.slots.iter().flatten() {
+            word.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// A handle to the same entries, for another thread searching the same position.
+    pub(crate) fn share(&self) -> Self {
+        Self {
+            slots: Arc::clone(&self.slots),
+            age: self.age,
+        }
+    }
+
+    /// Marks every stored entry as belonging to an earlier search.
+    pub(crate) fn new_search(&mut self) {
+        self.age = self.age.wrapping_add(1);
+    }
+
+    pub(crate) fn get(&self, key: u64) -> Option<TranspositionEntry> {
+        let [check, data] = &self.slots[self.index(key)];
+        let data = data.load(Ordering::Relaxed);
+        (data != 0 && check.load(Ordering::Relaxed) ^ data == key)
+            .then(|| TranspositionEntry::unpack(key, data))
+    }
+
+    /// Stores a search of the position with `key`, unless its score is too large to pack.
+    pub(crate) fn store(
+        &self,
+        key: u64,
+        depth: usize,
+        score: i64,
+        bound: Bound,
+        best_move: Option<(usize, usize)>,
+    ) {
+        if i32::try_from(score).is_err() {
+            return;
+        }
+        let age = self.age;
+        let [check, data] = &self.slots[self.index(key)];
+        let old = data.load(Ordering::Relaxed);
+        let replace = old == 0 || {
+            let old_key = check.load(Ordering::Relaxed) ^ old;
+            let entry = TranspositionEntry::unpack(old_key, old);
+            entry.key == key || entry.age != age || entry.depth <= depth
+        };
+        if replace {
+            let entry = TranspositionEntry {
+                key,
+                depth,
+                score,
+                bound,
+                best_move,
+                age,
+            }
+            .pack();
+            data.store(entry, Ordering::Relaxed);
+            check.store(key ^ entry, Ordering::Relaxed);
+        }
+    }
+
+    fn index(&self, key: u64) -> usize {
+        key as usize & (self.slots.len() - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_round_trip() {
+        let mut table = TranspositionTable::new(16);
+        table.store(0xDEAD_BEEF, 7, -1234, Bound::Lower, Some((9, 3)));
+        table.store(0x1234, 300, 5, Bound::Upper, None);
+        let shared = table.share();
+
+        let entry = shared.get(0xDEAD_BEEF).unwrap();
+        assert_eq!(
+            (entry.depth, entry.score, entry.bound, entry.best_move),
+            (7, -1234, Bound::Lower, Some((9, 3)))
+        );
+        let entry = table.get(0x1234).unwrap();
+        assert_eq!(
+            (entry.depth, entry.bound, entry.best_move),
+            (255, Bound::Upper, None)
+        );
+        assert!(table.get(0x1235).is_none());
+
+        let copy = table.clone();
+        table.clear();
+        assert!(shared.get(0x1234).is_none());
+        assert!(copy.get(0x1234).is_some());
+    }
+}
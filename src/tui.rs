@@ -0,0 +1,440 @@
+//! A full-screen front end for the terminal: the board with a cursor, the moves played and
+//! the last bot's analysis, redrawn in place rather than printed again every turn.
+
+use std::{
+    io,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+use web_time::Instant;
+
+use crate::{
+    error::Error,
+    history::Move,
+    move_provider::ProviderError,
+    player::Player,
+    reversi::Reversi,
+    transcript::{self, Transcript},
+};
+
+/// The file `s` saves the game to and `l` loads it from.
+const SAVED_GAME_PATH: &str = "saved_game.txt";
+
+/// Bots' moves are shown no sooner than this, so that games between bots can be followed.
+const MIN_BOT_TURN: Duration = Duration::from_millis(600);
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+const KEYS: &str = "←↑↓→ move  enter play  p pass  u undo  r redo  t hint  s save  l load  q quit";
+
+/// What a search on another thread came up with.
+enum Thought {
+    /// A copy of the game that a controller chose its move in, to carry on from so that bots
+    /// keep what they learned, and the move.
+    Move(Box<Reversi>, Option<Result<(usize, usize), ProviderError>>),
+    Hint(Result<(usize, usize), Error>),
+}
+
+struct Thinking {
+    handle: JoinHandle<Thought>,
+    started: Instant,
+    hint: bool,
+}
+
+/// Plays `game` full screen until it is quit, with its controllers choosing their moves on
+/// another thread while a spinner shows they are thinking.
+pub fn run(game: Reversi) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = App::new(game).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+struct App {
+    game: Reversi,
+    cursor: (usize, usize),
+    /// What the last key did, or why it did nothing.
+    message: Option<String>,
+    thinking: Option<Thinking>,
+    /// Set when a controller failed, so that it is not asked again until the game moves.
+    stalled: bool,
+    quit: bool,
+}
+
+impl App {
+    fn new(mut game: Reversi) -> Self {
+        game.update_valid_moves();
+        let mid = game.board().size() / 2;
+        Self {
+            game,
+            cursor: (mid - 1, mid - 1),
+            message: None,
+            thinking: None,
+            stalled: false,
+            quit: false,
+        }
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            self.update_controller_turn();
+            terminal.draw(|frame| self.draw(frame))?;
+            if event::poll(Duration::from_millis(80))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        self.handle_key(key.code);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Hands the turn to a controller on its own thread, or plays the move it chose.
+    fn update_controller_turn(&mut self) {
+        if let Some(thinking) = &self.thinking {
+            let min_time = if thinking.hint {
+                Duration::ZERO
+            } else {
+                MIN_BOT_TURN
+            };
+            if thinking.handle.is_finished() && thinking.started.elapsed() >= min_time {
+                let thought = self.thinking.take().unwrap().handle.join();
+                self.finish_thinking(thought.expect("the search's thread panicked"));
+            }
+            return;
+        }
+
+        let player = self.game.current_player();
+        if self.stalled || self.game.is_game_over() || self.game.controller(player).is_human() {
+            return;
+        }
+        if self.game.legal_moves().is_empty() {
+            self.game.make_move(Move::Pass);
+            return;
+        }
+        let mut game = self.game.clone();
+        self.think(false, move || {
+            let chosen = game.controller_move();
+            Thought::Move(Box::new(game), chosen)
+        });
+    }
+
+    /// Runs `search` on its own thread, with a spinner showing until it is done.
+    fn think(&mut self, hint: bool, search: impl FnOnce() -> Thought + Send + 'static) {
+        self.thinking = Some(Thinking {
+            handle: thread::spawn(search),
+            started: Instant::now(),
+            hint,
+        });
+    }
+
+    fn finish_thinking(&mut self, thought: Thought) {
+        let player = self.game.current_player();
+        match thought {
+            Thought::Move(game, chosen) => {
+                self.game = *game;
+                match chosen {
+                    Some(Ok(coord)) => self.game.make_move(Move::Place(coord)),
+                    Some(Err(error)) => {
+                        self.message = Some(format!("{player}'s controller failed: {error}"));
+                        self.stalled = true;
+                    }
+                    None => {}
+                }
+            }
+            Thought::Hint(Ok(coord)) => {
+                self.cursor = coord;
+                let square = transcript::square_name(coord, self.game.board().size());
+                self.message = Some(format!("Hint: {square}"));
+            }
+            Thought::Hint(Err(error)) => self.message = Some(error.to_string()),
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode) {
+        if matches!(code, KeyCode::Char('q') | KeyCode::Esc) {
+            self.quit = true;
+            return;
+        }
+        if self.thinking.is_some() {
+            return;
+        }
+
+        let last = self.game.board().size() - 1;
+        let (row, col) = self.cursor;
+        let result = match code {
+            KeyCode::Up => return self.cursor = (row.saturating_sub(1), col),
+            KeyCode::Down => return self.cursor = ((row + 1).min(last), col),
+            KeyCode::Left => return self.cursor = (row, col.saturating_sub(1)),
+            KeyCode::Right => return self.cursor = (row, (col + 1).min(last)),
+            KeyCode::Enter | KeyCode::Char(' ') if self.is_human_turn() => self
+                .game
+                .play(self.cursor)
+                .map(|()| None)
+                .map_err(|error| self.game.describe_move_error(error)),
+            KeyCode::Char('p') if self.is_human_turn() => self
+                .game
+                .pass()
+                .map(|()| None)
+                .map_err(|error| error.to_string()),
+            KeyCode::Char('u') => self
+                .game
+                .undo_to_human_turn()
+                .map(|()| None)
+                .map_err(|error| error.to_string()),
+            KeyCode::Char('r') => self
+                .game
+                .redo()
+                .map(|()| None)
+                .map_err(|error| error.to_string()),
+            KeyCode::Char('t') => {
+                let game = self.game.clone();
+                return self.think(true, move || Thought::Hint(game.hint()));
+            }
+            KeyCode::Char('s') => Transcript::from_game(&self.game)
+                .save(SAVED_GAME_PATH)
+                .map(|()| Some(format!("Saved to {SAVED_GAME_PATH}")))
+                .map_err(|error| error.to_string()),
+            KeyCode::Char('l') => Transcript::load(SAVED_GAME_PATH)
+                .and_then(|transcript| transcript.replay(&mut self.game))
+                .map(|()| Some(format!("Loaded {SAVED_GAME_PATH}")))
+                .map_err(|error| error.to_string()),
+            _ => return,
+        };
+        // a controller that failed may manage from the new position, so it is asked again
+        if code != KeyCode::Char('s') {
+            self.stalled = false;
+        }
+        self.message = result.unwrap_or_else(Some);
+    }
+
+    fn is_human_turn(&self) -> bool {
+        self.game.controller(self.game.current_player()).is_human()
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, status, keys] = Layout::vertical([
+            Constraint::Min(0),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let board_width = 3 * self.game.board().size() as u16 + 6;
+        let [board, side] =
+            Layout::horizontal([Constraint::Length(board_width), Constraint::Min(24)]).areas(main);
+        let [moves, analysis] =
+            Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(side);
+
+        self.draw_board(frame, board);
+        self.draw_moves(frame, moves);
+        self.draw_analysis(frame, analysis);
+        frame.render_widget(Paragraph::new(self.status()), status);
+        frame.render_widget(
+            Paragraph::new(KEYS).style(Style::default().fg(Color::DarkGray)),
+            keys,
+        );
+    }
+
+    /// Pieces are discs in their player's colour, with the last move in bold and the pieces
+    /// it flipped dimmed. Legal moves for a human are dots, and the cursor is highlighted.
+    fn draw_board(&self, frame: &mut Frame, area: Rect) {
+        let board = self.game.board();
+        let size = board.size();
+        let (last_move, flipped) = match self.game.history().last() {
+            Some((_, Move::Place(coord), flips)) => (Some(coord), board.coords(flips).collect()),
+            _ => (None, Vec::new()),
+        };
+        let legal_moves = if self.is_human_turn() && self.thinking.is_none() {
+            self.game.legal_moves()
+        } else {
+            Vec::new()
+        };
+
+        let header: String = (0..size)
+            .map(|col| format!(" {} ", (b'a' + col as u8) as char))
+            .collect();
+        let mut lines = vec![Line::from(format!("   {header}"))];
+        for row in 0..size {
+            let mut spans = vec![Span::raw(format!("{:>2} ", size - row))];
+            for col in 0..size {
+                let coord = (row, col);
+                let (symbol, mut style) = match board.get(coord) {
+                    Some(player) => ("●", Style::default().fg(player_colour(player))),
+                    None if legal_moves.contains(&coord) => {
+                        ("·", Style::default().fg(Color::Yellow))
+                    }
+                    None => (" ", Style::default()),
+                };
+                if last_move == Some(coord) {
+                    style = style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                } else if flipped.contains(&coord) {
+                    style = style.add_modifier(Modifier::DIM);
+                }
+                style = if coord == self.cursor {
+                    style.bg(Color::Gray)
+                } else if (row + col) % 2 == 0 {
+                    style.bg(Color::Rgb(24, 64, 40))
+                } else {
+                    style.bg(Color::Rgb(20, 56, 34))
+                };
+                spans.push(Span::styled(format!(" {symbol} "), style));
+            }
+            lines.push(Line::from(spans));
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(" Board ")),
+            area,
+        );
+    }
+
+    /// The most recent moves that fit, numbered by turn.
+    fn draw_moves(&self, frame: &mut Frame, area: Rect) {
+        let size = self.game.board().size();
+        let lines: Vec<Line> = self
+            .game
+            .history()
+            .moves()
+            .enumerate()
+            .map(|(i, (player, m))| {
+                let name = match m {
+                    Move::Place(coord) => transcript::square_name(coord, size),
+                    Move::Pass => String::from("pass"),
+                };
+                Line::from(vec![
+                    Span::raw(format!("{:>3}. ", i + 1)),
+                    Span::styled(
+                        format!("{player:<5} "),
+                        Style::default().fg(player_colour(player)),
+                    ),
+                    Span::raw(name),
+                ])
+            })
+            .collect();
+        let visible = area.height.saturating_sub(2) as usize;
+        let skipped = lines.len().saturating_sub(visible);
+        frame.render_widget(
+            Paragraph::new(lines[skipped..].to_vec()).block(Block::bordered().title(" Moves ")),
+            area,
+        );
+    }
+
+    fn draw_analysis(&self, frame: &mut Frame, area: Rect) {
+        let text = match (self.game.history().last(), self.game.last_analysis()) {
+            (Some((player, _, _)), Some(analysis)) if !self.game.controller(player).is_human() => {
+                format!("{player}'s last move:\n{analysis}")
+            }
+            _ => String::new(),
+        };
+        frame.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .block(Block::bordered().title(" Analysis ")),
+            area,
+        );
+    }
+
+    fn status(&self) -> Line<'static> {
+        let score = |player| {
+            Span::styled(
+                format!("{player} {}", self.game.score(player)),
+                Style::default().fg(player_colour(player)),
+            )
+        };
+        let player = self.game.current_player();
+        let state = if let Some(thinking) = &self.thinking {
+            let elapsed = thinking.started.elapsed();
+            let frame = (elapsed.as_millis() / 80) as usize % SPINNER.len();
+            let task = if thinking.hint {
+                String::from("Finding a hint")
+            } else {
+                format!("{player} is thinking")
+            };
+            format!("{task} {} {:.1}s", SPINNER[frame], elapsed.as_secs_f64())
+        } else if self.game.is_game_over() {
+            match self.game.get_winner() {
+                Some(winner) => format!("{winner} wins"),
+                None => String::from("It's a draw"),
+            }
+        } else if self.is_human_turn() && self.game.legal_moves().is_empty() {
+            format!("{player} has no legal move, press p to pass")
+        } else {
+            format!("{player} to move")
+        };
+
+        let mut spans = vec![
+            score(Player::Green),
+            Span::raw(" - "),
+            score(Player::Red),
+            Span::raw(format!(" | {state}")),
+        ];
+        if let Some(message) = &self.message {
+            spans.push(Span::styled(
+                format!(" | {message}"),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        Line::from(spans)
+    }
+}
+
+fn player_colour(player: Player) -> Color {
+    match player {
+        Player::Green => Color::LightGreen,
+        Player::Red => Color::LightRed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    #[test]
+    fn test_draws_the_board_and_moves() {
+        let mut app = App::new(Reversi::new(None));
+        app.handle_key(KeyCode::Down);
+        assert_eq!(app.cursor, (4, 3));
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.message.as_deref(), Some("d4 is already taken"));
+        app.handle_key(KeyCode::Down);
+        app.handle_key(KeyCode::Enter);
+        assert_eq!(app.message, None);
+        assert_eq!(app.game.history().len(), 1);
+
+        let mut terminal = Terminal::new(TestBackend::new(70, 16)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains(" a  b  c  d  e  f  g  h "));
+        assert!(screen.contains("1. Green d3"));
+        assert!(screen.contains("Green 4 - Red 1 | Red to move"));
+
+        app.handle_key(KeyCode::Char('t'));
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let status: String = (0..70)
+            .map(|x| terminal.backend().buffer()[(x, 14)].symbol().to_string())
+            .collect();
+        assert!(status.contains("Finding a hint"));
+        while app.thinking.is_some() {
+            thread::sleep(Duration::from_millis(10));
+            app.update_controller_turn();
+        }
+        assert!(app.game.legal_moves().contains(&app.cursor));
+        assert!(app.message.unwrap().starts_with("Hint: "));
+    }
+}
@@ -0,0 +1,453 @@
+use std::{fmt, fs, io, path::Path};
+
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{
+    board::Board,
+    bot::Bot,
+    bot_algorithm::BotAlgorithm,
+    bot_heuristic::{BotHeuristic, CompositeWeights},
+    history::Move,
+    pattern::{PatternWeights, PatternWeightsError},
+    player::Player,
+    reversi::Reversi,
+};
+
+/// Tuned weights are stored as hundredths of a disc, since the tables hold integers.
+const WEIGHT_SCALE: f64 = 100.0;
+
+/// How many discs of predicted advantage make a win about 73% likely in logistic tuning.
+const LOGISTIC_SCALE: f64 = 10.0;
+
+/// Training games open with this many random moves so that they don't all repeat each other.
+/// This is more than in `selfplay` games, since the tuner learns from unbalanced positions as
+/// well as even ones.
+const TRAINING_RANDOM_PLIES: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq, strum::Display, strum::EnumIter, strum::EnumString, Debug)]
+pub enum TuneMethod {
+    /// Least-squares regression of the final disc difference.
+    LeastSquares,
+    /// Texel-style tuning: fits a logistic curve of the evaluation to the game's outcome.
+    Logistic,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TuneSettings {
+    pub method: TuneMethod,
+    pub epochs: usize,
+    pub learning_rate: f64,
+    /// Share of the positions held out to measure the tuned weights on.
+    pub validation_fraction: f64,
+    pub stages: usize,
+    pub seed: u64,
+}
+
+impl Default for TuneSettings {
+    fn default() -> Self {
+        Self {
+            method: TuneMethod::LeastSquares,
+            epochs: 20,
+            learning_rate: 0.005,
+            validation_fraction: 0.1,
+            stages: 4,
+            seed: 0,
+        }
+    }
+}
+
+impl TuneSettings {
+    /// Checks the settings that tuning would fail on, so that they can be rejected before
+    /// positions are gathered.
+    pub fn validate(&self) -> Result<(), TuneError> {
+        if !(0.0..1.0).contains(&self.validation_fraction) {
+            return Err(TuneError::InvalidSettings(format!(
+                "the validation fraction must be at least 0 and below 1, not {}",
+                self.validation_fraction
+            )));
+        }
+        if self.stages == 0 {
+            return Err(TuneError::InvalidSettings(String::from(
+                "there must be at least one stage",
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A position and how the game it was taken from ended.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct LabelledPosition {
+    pub board: Board,
+    pub to_move: Player,
+    /// The final number of Green pieces minus Red pieces.
+    pub outcome: i64,
+}
+
+/// Mean errors of the tuned weights, alongside those of always predicting a draw.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TuneReport {
+    pub training_positions: usize,
+    pub validation_positions: usize,
+    pub training_error: f64,
+    pub validation_error: f64,
+    pub baseline_validation_error: f64,
+}
+
+impl fmt::Display for TuneReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Training error:   {:.4} ({} positions)",
+            self.training_error, self.training_positions
+        )?;
+        writeln!(
+            f,
+            "Validation error: {:.4} ({} positions)",
+            self.validation_error, self.validation_positions
+        )?;
+        write!(
+            f,
+            "Validation error when always predicting a draw: {:.4}",
+            self.baseline_validation_error
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum TuneError {
+    Io(io::Error),
+    /// A line of a positions file that could not be read, counting from 1.
+    Parse {
+        line: usize,
+        reason: String,
+    },
+    Weights(PatternWeightsError),
+    /// Every position needs to be on a board of the same size, and there must be some.
+    NoPositions,
+    MixedBoardSizes,
+    /// A setting that tuning cannot run with, and why.
+    InvalidSettings(String),
+}
+
+impl fmt::Display for TuneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "could not access the file: {error}"),
+            Self::Parse { line, reason } => write!(f, "line {line}: {reason}"),
+            Self::Weights(error) => write!(f, "{error}"),
+            Self::NoPositions => write!(f, "there are no positions to tune on"),
+            Self::MixedBoardSizes => write!(f, "the positions are not all on the same board size"),
+            Self::InvalidSettings(reason) => write!(f, "invalid settings: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for TuneError {}
+
+impl From<io::Error> for TuneError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<PatternWeightsError> for TuneError {
+    fn from(error: PatternWeightsError) -> Self {
+        Self::Weights(error)
+    }
+}
+
+/// Reads one position per line: the squares as read by [`Board::from_squares_str`], the
+/// player to move as `X` or `O`, then the final disc difference for Green, separated by
+/// spaces. Blank lines and lines starting with `#` are skipped.
+pub fn parse_positions(text: &str) -> Result<Vec<LabelledPosition>, TuneError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            parse_position(line).map_err(|reason| TuneError::Parse {
+                line: i + 1,
+                reason,
+            })
+        })
+        .collect()
+}
+
+fn parse_position(line: &str) -> Result<LabelledPosition, String> {
+    let fields: Vec<_> = line.split_whitespace().collect();
+    let [squares, to_move, outcome] = fields[..] else {
+        return Err(format!("expected 3 fields, found {}", fields.len()));
+    };
+
+    let board = Board::from_squares_str(squares).map_err(|error| error.to_string())?;
+    let to_move = match to_move.chars().collect::<Vec<_>>()[..] {
+        [symbol] => Player::from_symbol(symbol),
+        _ => None,
+    }
+    .ok_or_else(|| format!("unexpected player to move '{to_move}'"))?;
+    let outcome = outcome
+        .parse()
+        .map_err(|_| format!("'{outcome}' is not a disc difference"))?;
+
+    Ok(LabelledPosition {
+        board,
+        to_move,
+        outcome,
+    })
+}
+
+/// Writes a position in the format read by [`parse_positions`].
+pub fn format_position(position: &LabelledPosition) -> String {
+    format!(
+        "{} {} {}",
+        position.board.to_squares_string(),
+        position.to_move.symbol(),
+        position.outcome
+    )
+}
+
+pub fn load_positions(path: impl AsRef<Path>) -> Result<Vec<LabelledPosition>, TuneError> {
+    parse_positions(&fs::read_to_string(path)?)
+}
+
+pub fn save_positions(
+    path: impl AsRef<Path>,
+    positions: &[LabelledPosition],
+) -> Result<(), TuneError> {
+    let text: String = positions
+        .iter()
+        .map(|position| format_position(position) + "\n")
+        .collect();
+    fs::write(path, text)?;
+    Ok(())
+}
+
+/// Plays `games` games between shallow bots, after a few random opening moves, and labels
+/// every position of each game with its result.
+pub fn self_play_positions(games: usize, seed: u64) -> Vec<LabelledPosition> {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut bot = Bot::new(
+        BotAlgorithm::NegaMax,
+        Some(2),
+        BotHeuristic::Composite(CompositeWeights::default()),
+    );
+
+    let mut positions = Vec::new();
+    for _ in 0..games {
+        let mut game = Reversi::new(None);
+        let mut game_positions = Vec::new();
+        game.update_valid_moves();
+        for ply in 0.. {
+            if !Reversi::anyone_can_move(game.board()) {
+                break;
+            }
+            if !game.valid_moves().is_empty() {
+                game_positions.push((game.board().clone(), game.current_player()));
+                let coord = if ply < TRAINING_RANDOM_PLIES {
+                    game.valid_moves()[rng.gen_range(0..game.valid_moves().len())]
+                } else {
+                    bot.get_move(game.clone())
+                };
+                game.make_move(Move::Place(coord));
+            } else {
+                game.make_move(Move::Pass);
+            }
+        }
+
+        let board = game.board();
+        let outcome = board.count_for_player(Player::Green) as i64
+            - board.count_for_player(Player::Red) as i64;
+        positions.extend(
+            game_positions
+                .into_iter()
+                .map(|(board, to_move)| LabelledPosition {
+                    board,
+                    to_move,
+                    outcome,
+                }),
+        );
+    }
+    positions
+}
+
+/// Fits pattern weights to `positions` by stochastic gradient descent, holding out a share
+/// of them to report how well the weights generalise.
+pub fn tune(
+    positions: &[LabelledPosition],
+    settings: &TuneSettings,
+) -> Result<(PatternWeights, TuneReport), TuneError> {
+    settings.validate()?;
+    let size = positions
+        .first()
+        .ok_or(TuneError::NoPositions)?
+        .board
+        .size();
+    if positions
+        .iter()
+        .any(|position| position.board.size() != size)
+    {
+        return Err(TuneError::MixedBoardSizes);
+    }
+
+    // positions from one game are usually next to each other, so holding out the last ones
+    // keeps most games entirely on one side of the split
+    let positions: Vec<_> = positions.iter().collect();
+    let validation_len = (positions.len() as f64 * settings.validation_fraction) as usize;
+    let (training, validation) = positions.split_at(positions.len() - validation_len);
+    let mut training = training.to_vec();
+    let mut rng = SmallRng::seed_from_u64(settings.seed);
+
+    let mut weights = PatternWeights::zeroed(size, settings.stages);
+    let mut model: Vec<Vec<Vec<f64>>> = weights
+        .stages
+        .iter()
+        .map(|tables| tables.iter().map(|table| vec![0.0; table.len()]).collect())
+        .collect();
+
+    for _ in 0..settings.epochs {
+        training.shuffle(&mut rng);
+        for position in &training {
+            let features = features(&weights, position);
+            let stage = weights.stage(&position.board);
+            let prediction = predict(&model[stage], &features);
+            let gradient = match settings.method {
+                TuneMethod::LeastSquares => prediction - position.outcome as f64,
+                TuneMethod::Logistic => {
+                    let p = sigmoid(prediction);
+                    (p - win_probability(position)) * p * (1.0 - p) / LOGISTIC_SCALE
+                }
+            };
+            let step = settings.learning_rate * gradient;
+            for &(pattern, index, sign) in &features {
+                model[stage][pattern][index] -= step * sign;
+            }
+        }
+    }
+
+    for (tables, model_tables) in weights.stages.iter_mut().zip(&model) {
+        for (table, model_table) in tables.iter_mut().zip(model_tables) {
+            for (weight, &value) in table.iter_mut().zip(model_table) {
+                *weight = (value * WEIGHT_SCALE).round() as i32;
+            }
+        }
+    }
+
+    let error = |positions: &[&LabelledPosition], predict: &dyn Fn(&LabelledPosition) -> f64| {
+        let total: f64 = positions
+            .iter()
+            .map(|position| {
+                let prediction = predict(position);
+                match settings.method {
+                    TuneMethod::LeastSquares => (prediction - position.outcome as f64).powi(2),
+                    TuneMethod::Logistic => {
+                        (sigmoid(prediction) - win_probability(position)).powi(2)
+                    }
+                }
+            })
+            .sum();
+        total / positions.len().max(1) as f64
+    };
+    let tuned = |position: &LabelledPosition| {
+        weights.evaluate(&position.board, Player::Green) as f64 / WEIGHT_SCALE
+    };
+    let report = TuneReport {
+        training_positions: training.len(),
+        validation_positions: validation.len(),
+        training_error: error(&training, &tuned),
+        validation_error: error(validation, &tuned),
+        baseline_validation_error: error(validation, &|_| 0.0),
+    };
+    Ok((weights, report))
+}
+
+/// Every pattern placement of the position as `(pattern, index, sign)`: Green's view of the
+/// board counts for Green and Red's view against, matching [`PatternWeights::evaluate`].
+fn features(weights: &PatternWeights, position: &LabelledPosition) -> Vec<(usize, usize, f64)> {
+    let green = position.board.bits_for_player(Player::Green);
+    let red = position.board.bits_for_player(Player::Red);
+    weights
+        .features(green, red)
+        .map(|(pattern, index)| (pattern, index, 1.0))
+        .chain(
+            weights
+                .features(red, green)
+                .map(|(pattern, index)| (pattern, index, -1.0)),
+        )
+        .collect()
+}
+
+fn predict(tables: &[Vec<f64>], features: &[(usize, usize, f64)]) -> f64 {
+    features
+        .iter()
+        .map(|&(pattern, index, sign)| tables[pattern][index] * sign)
+        .sum()
+}
+
+fn sigmoid(prediction: f64) -> f64 {
+    1.0 / (1.0 + (-prediction / LOGISTIC_SCALE).exp())
+}
+
+/// 1 if Green won, 0 if Red won and a half for a draw.
+fn win_probability(position: &LabelledPosition) -> f64 {
+    (position.outcome.signum() as f64 + 1.0) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_positions_round_trip() {
+        let positions = self_play_positions(2, 1);
+        let text: String = positions
+            .iter()
+            .map(|position| format_position(position) + "\n")
+            .collect();
+        assert_eq!(parse_positions(&text).unwrap(), positions);
+
+        assert!(matches!(
+            parse_positions("# comment\n\n---- X 3"),
+            Err(TuneError::Parse { line: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn test_tuning_beats_the_baseline() {
+        let positions = self_play_positions(40, 0);
+        for method in [TuneMethod::LeastSquares, TuneMethod::Logistic] {
+            let settings = TuneSettings {
+                method,
+                epochs: 5,
+                validation_fraction: 0.2,
+                ..Default::default()
+            };
+            let (weights, report) = tune(&positions, &settings).unwrap();
+            assert_eq!(weights.stages.len(), settings.stages);
+            assert!(report.validation_error < report.baseline_validation_error);
+        }
+    }
+
+    #[test]
+    fn test_invalid_settings_are_rejected() {
+        let positions = self_play_positions(1, 0);
+        for settings in [
+            TuneSettings {
+                validation_fraction: 1.5,
+                ..Default::default()
+            },
+            TuneSettings {
+                validation_fraction: f64::NAN,
+                ..Default::default()
+            },
+            TuneSettings {
+                stages: 0,
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                tune(&positions, &settings),
+                Err(TuneError::InvalidSettings(_))
+            ));
+        }
+    }
+}
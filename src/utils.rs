@@ -1,4 +1,48 @@
+use std::{
+    fmt::{Debug, Display},
+    str::FromStr,
+};
+
+use strum::IntoEnumIterator;
+use text_io::{read, try_read};
+
 pub fn clear_terminal() {
     #[cfg(target_os = "windows")]
     print!("{}[2J", 27 as char);
 }
+
+/// Lists every variant of `T` under `message` until the user types one of them.
+pub fn prompt_choice<T>(message: &str) -> T
+where
+    T: IntoEnumIterator + Display + FromStr,
+    T::Err: Debug,
+{
+    loop {
+        println!("{} (caps matter):", message);
+        for choice in T::iter() {
+            println!("{}", choice);
+        }
+        println!();
+        let choice: Option<T> = try_read!().ok();
+        clear_terminal();
+        if let Some(choice) = choice {
+            return choice;
+        }
+    }
+}
+
+/// Lists `names` under `message` until the user types one of them, and returns its index.
+pub fn prompt_name(message: &str, names: &[String]) -> usize {
+    loop {
+        println!("{} (caps matter):", message);
+        for name in names {
+            println!("{}", name);
+        }
+        println!();
+        let line: String = read!("{}\n");
+        clear_terminal();
+        if let Some(index) = names.iter().position(|name| name == line.trim()) {
+            return index;
+        }
+    }
+}
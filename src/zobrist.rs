@@ -0,0 +1,42 @@
+use crate::{board::Bitboard, player::Player};
+
+const SQUARES: usize = Bitboard::BITS as usize;
+
+/// One key per player per square, generated at compile time so hashes are stable between runs.
+const PIECE_KEYS: [[u64; SQUARES]; 2] = {
+    let mut keys = [[0; SQUARES]; 2];
+    let mut state = 0x4b41_525f_5245_5653;
+    let mut i = 0;
+    while i < 2 * SQUARES {
+        let (key, next_state) = splitmix64(state);
+        keys[i / SQUARES][i % SQUARES] = key;
+        state = next_state;
+        i += 1;
+    }
+    keys
+};
+
+/// Mixed into a position's key when Red is the player to move.
+pub(crate) const RED_TO_MOVE_KEY: u64 = splitmix64(0x5349_4445_544f_4d56).0;
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    (z ^ (z >> 31), state)
+}
+
+/// XOR of the keys for `player` owning every square set in `bits`.
+pub(crate) fn pieces_key(player: Player, mut bits: Bitboard) -> u64 {
+    let keys = match player {
+        Player::Green => &PIECE_KEYS[0],
+        Player::Red => &PIECE_KEYS[1],
+    };
+    let mut key = 0;
+    while bits != 0 {
+        key ^= keys[bits.trailing_zeros() as usize];
+        bits &= bits - 1;
+    }
+    key
+}